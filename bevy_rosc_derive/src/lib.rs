@@ -0,0 +1,116 @@
+//! Derive macros for [bevy_rosc](https://crates.io/crates/bevy_rosc)
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Error, Field, Fields, Index, Type};
+
+/// Derives `bevy_rosc::OscArgs` for a struct, decoding its fields from OSC arguments in the
+/// order they are declared
+///
+/// Every field has to implement `bevy_rosc::OscArg`, so type parameters get an `OscArg` bound.
+/// Fields of type `Option<T>` are optional and may only be followed by other optional fields, a
+/// required field after them is a compile error.
+#[proc_macro_derive(OscArgs)]
+pub fn derive_osc_args(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(mut input: DeriveInput) -> Result<TokenStream2, Error> {
+    for param in input.generics.type_params_mut() {
+        param.bounds.push(parse_quote!(::bevy_rosc::OscArg));
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "OscArgs can only be derived for structs",
+            ))
+        }
+    };
+    check_optional_order(fields)?;
+    let count = fields.len();
+    let types = fields.iter().map(|field| &field.ty);
+    let indices = 0..count;
+
+    let (decode, encode) = match fields {
+        Fields::Named(fields) => {
+            let names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+            (
+                quote! { Self { #(#names: ::bevy_rosc::OscArg::decode_arg(args, #indices)?,)* } },
+                quote! { #(::bevy_rosc::OscArg::encode_arg(&self.#names, &mut args);)* },
+            )
+        }
+        Fields::Unnamed(_) => {
+            let members: Vec<_> = (0..count).map(Index::from).collect();
+            (
+                quote! { Self(#(::bevy_rosc::OscArg::decode_arg(args, #indices)?,)*) },
+                quote! { #(::bevy_rosc::OscArg::encode_arg(&self.#members, &mut args);)* },
+            )
+        }
+        Fields::Unit => (quote! { Self }, quote! {}),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::bevy_rosc::OscArgs for #name #ty_generics #where_clause {
+            fn decode_args(
+                args: &[::bevy_rosc::rosc::OscType],
+            ) -> ::core::result::Result<Self, ::bevy_rosc::OscArgsError> {
+                // Also catches type parameters that are `Option`s
+                const {
+                    ::core::assert!(
+                        ::bevy_rosc::optional_args_last(
+                            &[#(<#types as ::bevy_rosc::OscArg>::OPTIONAL),*]
+                        ),
+                        "Required fields can't follow optional fields"
+                    )
+                };
+                ::bevy_rosc::OscArgsError::check_count(args, #count)?;
+                ::core::result::Result::Ok(#decode)
+            }
+
+            fn encode_args(&self) -> ::std::vec::Vec<::bevy_rosc::rosc::OscType> {
+                #[allow(unused_mut)]
+                let mut args = ::std::vec::Vec::with_capacity(#count);
+                #encode
+                args
+            }
+        }
+    })
+}
+
+/// Returns true if the type is written as `Option<T>`, possibly with a path like `std::option::`
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+/// Optional fields may only be followed by other optional fields, required fields after them
+/// could never be decoded
+fn check_optional_order<'a>(fields: impl IntoIterator<Item = &'a Field>) -> Result<(), Error> {
+    let mut optional = false;
+    for field in fields {
+        if is_option(&field.ty) {
+            optional = true;
+        } else if optional {
+            return Err(Error::new_spanned(
+                field,
+                "Required fields can't follow optional fields",
+            ));
+        }
+    }
+    Ok(())
+}
@@ -6,7 +6,7 @@ use bevy::prelude::*;
 use rosc::address::OscAddress;
 use rosc::OscMessage;
 
-use bevy_rosc::{BevyRoscPlugin, method_dispatcher_system};
+use bevy_rosc::{BevyRoscPlugin, OscAppExt};
 use bevy_rosc::OscMethod;
 
 #[derive(Component)]
@@ -16,7 +16,7 @@ struct MyOscMethod {
 
 impl OscMethod for MyOscMethod {
     fn get_addresses(&self) -> Vec<OscAddress> {
-        return vec![self.osc_address.clone()];
+        vec![self.osc_address.clone()]
     }
 
     // This method is called when an OSC message was successfully matched with the method
@@ -39,7 +39,7 @@ fn main() {
         // Add the bevy_rosc plugin and have it listen on port 31337
         .add_plugins(BevyRoscPlugin::new("0.0.0.0:31337").unwrap())
         // Add dispatcher system for MyOscMethod
-        .add_systems(Update, method_dispatcher_system::<MyOscMethod>)
+        .add_osc_method::<MyOscMethod>()
         .add_systems(Startup, startup)
         .run();
 }
@@ -5,7 +5,10 @@ extern crate bevy_rosc;
 use bevy::prelude::*;
 
 use bevy_rosc::OscMethod;
-use bevy_rosc::{method_dispatcher_system, MultiAddressOscMethod, OscDispatchEvent};
+use bevy_rosc::{
+    method_dispatcher_system, osc_receive_system, MultiAddressOscMethod, OscDispatchEvent,
+    OscErrorEvent,
+};
 use bevy_rosc::{OscDispatcher, OscUdpServer};
 
 fn startup(mut commands: Commands) {
@@ -33,27 +36,6 @@ fn print_received_osc_packets(
     }
 }
 
-/// System that receives messages via UDP and then forwards them to the dispatcher
-fn osc_receive_system(
-    mut osc_dispatcher: ResMut<OscDispatcher>,
-    mut query: Query<&mut OscUdpServer>,
-    event_writer: EventWriter<OscDispatchEvent>,
-) {
-    let mut osc_packets = vec![];
-    for osc_udp_server in query.iter_mut() {
-        loop {
-            if let Ok(o) = osc_udp_server.recv() {
-                match o {
-                    Some(p) => osc_packets.push(p),
-                    None => break,
-                }
-            }
-        }
-    }
-
-    osc_dispatcher.dispatch(osc_packets, event_writer);
-}
-
 fn main() {
     App::new()
         .add_plugins(MinimalPlugins)
@@ -61,12 +43,16 @@ fn main() {
         .insert_resource(OscDispatcher::default())
         // Event sent by the dispatcher
         .add_event::<OscDispatchEvent>()
+        // Event sent when receiving or dispatching fails
+        .add_event::<OscErrorEvent>()
         // System that received the dispatch event and attempts to match received messages with all `MultiAddressOscMethod` components
         .add_systems(
             PreUpdate,
             (
-                osc_receive_system,
-                method_dispatcher_system::<MultiAddressOscMethod>.after(osc_receive_system),
+                // Receive packets from all `OscUdpServer` components and forward them to the dispatcher
+                osc_receive_system::<OscUdpServer>,
+                method_dispatcher_system::<MultiAddressOscMethod>
+                    .after(osc_receive_system::<OscUdpServer>),
             ),
         )
         .add_systems(Startup, startup)
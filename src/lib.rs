@@ -39,16 +39,28 @@
 //! }
 //! ```
 //!
+//! # TCP
+//! Besides the UDP server opened by the plugin, you can spawn an [`OscTcpServer`] to receive
+//! packets that don't fit into a single datagram. Both the OSC 1.0 length-prefix framing and the
+//! OSC 1.1 SLIP framing are supported, see [`OscStreamFraming`].
+//!
+//! # Custom transports
+//! Every packet source implements [`OscTransport`]. To receive packets from your own transport
+//! component, register it with [`OscAppExt::add_osc_transport`], just like the plugin does for the
+//! included UDP and TCP transports. This adds an [`osc_receive_system`] for it and allows sending
+//! through it with the [`OscTransportRegistry`].
+//!
 //! # Advanced usage
 //! There is the option to add custom osc method components.
-//! Your component just has to implement [`OscMethod`] and you need to add a [`method_dispatcher_system`] for it.
+//! Your component just has to implement [`OscMethod`] and you need to register it, which adds a [`method_dispatcher_system`] for it.
 //! Now your component will receive OSC messages at it's address(es).
+//! [`OscAppExt::add_osc_method`] adds it to the [`OscSet::Dispatch`] set, so it runs in the same frame as the included methods.
 //! 
 //! ```no_run
 //! extern crate bevy_rosc;
 //! use bevy::prelude::*;
 //! use bevy_rosc::OscMethod;
-//! use bevy_rosc::{method_dispatcher_system, BevyRoscPlugin};
+//! use bevy_rosc::{BevyRoscPlugin, OscAppExt};
 //! use rosc::address::OscAddress;
 //! use rosc::OscMessage;
 //! 
@@ -78,21 +90,102 @@
 //!     App::new()
 //!         .add_plugins(MinimalPlugins)
 //!         .add_plugins(BevyRoscPlugin::new("0.0.0.0:31337").unwrap())
-//!         .add_systems(Update, method_dispatcher_system::<MyOscMethod>) // <-- Add dispatcher system for your method
+//!         .add_osc_method::<MyOscMethod>() // <-- Add dispatcher system for your method
 //!         .add_systems(Startup, startup)
 //!         .run();
 //! }
 //! ```
-extern crate rosc;
+pub extern crate rosc;
 
+mod osc_address_space;
+mod osc_app_ext;
+mod osc_args;
+mod osc_bind;
 mod osc_dispatcher;
+mod osc_error;
+mod osc_handler;
+mod osc_latest_value;
+mod osc_loopback;
+mod osc_matcher_cache;
+mod osc_meta;
 mod osc_method;
+mod osc_outbox;
+mod osc_publish;
+#[cfg(feature = "oscquery")]
+mod osc_query;
+mod osc_queue;
+mod osc_receive_thread;
+mod osc_received;
+mod osc_reply;
+mod osc_send;
+mod osc_set;
+mod osc_stream;
+mod osc_tcp_client;
+mod osc_tcp_server;
+mod osc_transport;
 mod osc_udp_client;
+mod osc_udp_options;
 mod osc_udp_server;
+#[cfg(unix)]
+mod osc_unix_datagram_client;
+#[cfg(unix)]
+mod osc_unix_datagram_server;
+#[cfg(unix)]
+mod osc_unix_stream_client;
+#[cfg(unix)]
+mod osc_unix_stream_server;
 mod plugin;
 
-pub use osc_dispatcher::{method_dispatcher_system, OscDispatchEvent, OscDispatcher};
-pub use osc_method::{MultiAddressOscMethod, OscMethod, SingleAddressOscMethod};
+pub use bevy_rosc_derive::OscArgs;
+pub use osc_address_space::OscAddressSpace;
+pub use osc_app_ext::OscAppExt;
+pub use osc_args::{osc_type_tag, OscArg, OscArgs, OscArgsError};
+#[doc(hidden)]
+pub use osc_args::optional_args_last;
+pub use osc_bind::{osc_bind_system, OscBind, OscBindError, OscBindTarget};
+pub use osc_dispatcher::{
+    method_dispatcher_system, osc_method_dispatcher, OscDispatchEvent, OscDispatcher,
+    OscLatePolicy, OscScheduleError, DEFAULT_MAX_SCHEDULED, DEFAULT_MAX_SCHEDULE_HORIZON,
+};
+pub use osc_error::{OscErrorEvent, OscTransportError};
+pub use osc_handler::{osc_handler_system, OscHandler};
+pub use osc_latest_value::OscLatestValue;
+pub use osc_loopback::OscLoopback;
+pub use osc_matcher_cache::{OscMatcherCacheStats, DEFAULT_MATCHER_CACHE_CAPACITY};
+pub use osc_meta::OscMessageMeta;
+pub use osc_method::{
+    MultiAddressOscMethod, OscMethod, SingleAddressOscMethod, TypedOscMethod,
+};
+pub use osc_outbox::{osc_outbox_system, OscOutbox};
+pub use osc_publish::{osc_publish_system, OscPublish, OscPublishField, OscPublishMode};
+#[cfg(feature = "oscquery")]
+pub use osc_query::{
+    osc_query_namespace_system, osc_query_value_system, OscQueryAccess, OscQueryInfo,
+    OscQueryPlugin, OscQueryRange, OscQueryServer,
+};
+pub use osc_queue::{OscOverflowPolicy, OscQueuePolicy};
+pub use osc_received::{osc_unmatched_system, OscReceived, OscUnmatched};
+pub use osc_reply::{osc_reply_system, OscReplyEvent};
+pub use osc_send::{osc_send_system, OscSendEvent, OscSendTarget};
+pub use osc_set::{OscSchedules, OscSet};
+pub use osc_stream::{
+    OscStreamDecoder, OscStreamFraming, DEFAULT_MAX_FRAME_SIZE, DEFAULT_MAX_SEND_BUFFER_SIZE,
+};
+pub use osc_tcp_client::OscTcpClient;
+pub use osc_tcp_server::OscTcpServer;
+pub use osc_transport::{
+    osc_receive_system, OscReceivedPacket, OscTransport, OscTransportRegistry,
+};
 pub use osc_udp_client::OscUdpClient;
-pub use osc_udp_server::OscUdpServer;
-pub use plugin::BevyRoscPlugin;
+pub use osc_udp_options::OscUdpOptions;
+#[allow(deprecated)]
+pub use osc_udp_server::{OscUdpReceiveError, OscUdpServer};
+#[cfg(unix)]
+pub use osc_unix_datagram_client::OscUnixDatagramClient;
+#[cfg(unix)]
+pub use osc_unix_datagram_server::OscUnixDatagramServer;
+#[cfg(unix)]
+pub use osc_unix_stream_client::OscUnixStreamClient;
+#[cfg(unix)]
+pub use osc_unix_stream_server::OscUnixStreamServer;
+pub use plugin::{BevyRoscPlugin, OscBindFailurePolicy};
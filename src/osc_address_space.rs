@@ -0,0 +1,196 @@
+use crate::OscMethod;
+use bevy::prelude::*;
+use rosc::address::{Matcher, OscAddress};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// Characters that turn an address pattern part into a wildcard
+const PATTERN_CHARS: [char; 6] = ['*', '?', '[', ']', '{', '}'];
+
+/// Counts changes to the address spaces of all [`OscMethod`] types, so systems that mirror them
+/// know when to update
+#[derive(Resource, Default)]
+pub(crate) struct OscAddressSpaceRevision(pub(crate) u64);
+
+/// A single address part in the [`OscAddressSpace`]
+#[derive(Default)]
+struct AddressNode {
+    children: HashMap<String, AddressNode>,
+    /// Full address of this node, if any method listens on it
+    address: Option<OscAddress>,
+    /// Entities with a method listening on this node's address
+    entities: Vec<Entity>,
+}
+
+impl AddressNode {
+    fn is_empty(&self) -> bool {
+        self.children.is_empty() && self.entities.is_empty()
+    }
+
+    /// Collect all entities whose address matches the pattern made from `parts`
+    fn collect(&self, parts: &[&str], matcher: &Matcher, entities: &mut Vec<Entity>) {
+        let Some((part, rest)) = parts.split_first() else {
+            if let Some(address) = &self.address {
+                if matcher.match_address(address) {
+                    entities.extend_from_slice(&self.entities);
+                }
+            }
+            return;
+        };
+
+        if part.contains(PATTERN_CHARS) {
+            // Wildcards never span multiple parts, so only this level has to be scanned
+            for child in self.children.values() {
+                child.collect(rest, matcher, entities);
+            }
+        } else if let Some(child) = self.children.get(*part) {
+            child.collect(rest, matcher, entities);
+        }
+    }
+
+    /// Remove `entity` from the node at `parts`, pruning nodes that end up empty
+    fn remove(&mut self, parts: &[&str], entity: Entity) {
+        let Some((part, rest)) = parts.split_first() else {
+            self.entities.retain(|e| *e != entity);
+            if self.entities.is_empty() {
+                self.address = None;
+            }
+            return;
+        };
+
+        if let Some(child) = self.children.get_mut(*part) {
+            child.remove(rest, entity);
+            if child.is_empty() {
+                self.children.remove(*part);
+            }
+        }
+    }
+}
+
+/// Index of all addresses of the [`OscMethod`] components of type `T`, organised as a trie of
+/// address parts
+///
+/// It is created and kept up to date by [`method_dispatcher_system`](crate::method_dispatcher_system)
+/// using observers, which read [`OscMethod::get_addresses`] whenever a component is inserted.
+/// Literal address patterns are looked up in time proportional to the length of the address, and
+/// wildcard patterns only scan the address parts containing wildcards.
+#[derive(Resource)]
+pub struct OscAddressSpace<T> {
+    root: AddressNode,
+    /// Addresses of every indexed entity, needed to remove it again
+    addresses: HashMap<Entity, Vec<String>>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for OscAddressSpace<T> {
+    fn default() -> Self {
+        Self {
+            root: Default::default(),
+            addresses: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> OscAddressSpace<T> {
+    /// Add an entity listening on `addresses`
+    pub fn insert(&mut self, entity: Entity, addresses: Vec<OscAddress>) {
+        self.remove(entity);
+
+        let mut address_strings = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let address_string = address.to_string();
+            let mut node = &mut self.root;
+            for part in Self::split(&address_string) {
+                node = node.children.entry(part.to_string()).or_default();
+            }
+            if !node.entities.contains(&entity) {
+                node.entities.push(entity);
+            }
+            node.address = Some(address);
+            address_strings.push(address_string);
+        }
+        self.addresses.insert(entity, address_strings);
+    }
+
+    /// Remove an entity from all of its addresses
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some(addresses) = self.addresses.remove(&entity) {
+            for address in addresses {
+                self.root.remove(&Self::split(&address), entity);
+            }
+        }
+    }
+
+    /// Returns all entities with an address that matches the pattern of `matcher`
+    ///
+    /// Every entity is only returned once, even if several of its addresses match.
+    pub fn matching(&self, matcher: &Matcher) -> Vec<Entity> {
+        let mut entities = vec![];
+        self.root
+            .collect(&Self::split(&matcher.pattern), matcher, &mut entities);
+        if entities.len() > 1 {
+            entities.sort_unstable();
+            entities.dedup();
+        }
+        entities
+    }
+
+    /// Returns the addresses of an entity, if it is indexed
+    pub fn addresses(&self, entity: Entity) -> Option<&[String]> {
+        self.addresses.get(&entity).map(Vec::as_slice)
+    }
+
+    /// Returns the number of indexed entities
+    pub fn len(&self) -> usize {
+        self.addresses.len()
+    }
+
+    /// Returns true if no entities are indexed
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
+
+    fn split(address: &str) -> Vec<&str> {
+        address.split('/').skip(1).collect()
+    }
+}
+
+impl<T: OscMethod + Component> OscAddressSpace<T> {
+    /// Index all existing `T` components and register observers that keep the index up to date
+    ///
+    /// Does nothing if the index already exists.
+    pub fn init(world: &mut World) {
+        if world.contains_resource::<Self>() {
+            return;
+        }
+
+        let mut address_space = Self::default();
+        let mut query = world.query::<(Entity, &T)>();
+        for (entity, osc_method) in query.iter(world) {
+            address_space.insert(entity, osc_method.get_addresses());
+        }
+        world.insert_resource(address_space);
+        world.get_resource_or_init::<OscAddressSpaceRevision>().0 += 1;
+
+        world.add_observer(
+            |trigger: Trigger<OnInsert, T>,
+             query: Query<&T>,
+             mut address_space: ResMut<OscAddressSpace<T>>,
+             mut revision: ResMut<OscAddressSpaceRevision>| {
+                if let Ok(osc_method) = query.get(trigger.target()) {
+                    address_space.insert(trigger.target(), osc_method.get_addresses());
+                    revision.0 += 1;
+                }
+            },
+        );
+        world.add_observer(
+            |trigger: Trigger<OnReplace, T>,
+             mut address_space: ResMut<OscAddressSpace<T>>,
+             mut revision: ResMut<OscAddressSpaceRevision>| {
+                address_space.remove(trigger.target());
+                revision.0 += 1;
+            },
+        );
+    }
+}
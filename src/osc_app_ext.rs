@@ -0,0 +1,116 @@
+use crate::{
+    method_dispatcher_system, osc_receive_system, OscMethod, OscSchedules, OscSet, OscTransport,
+    OscTransportRegistry,
+};
+use bevy::ecs::component::Mutable;
+use bevy::ecs::schedule::InternedScheduleLabel;
+use bevy::prelude::*;
+use bevy::reflect::GetTypeRegistration;
+use rosc::address::OscAddress;
+use std::any::TypeId;
+use std::collections::HashMap;
+
+/// Returns the addresses of every component of one [`OscMethod`] type, by entity
+pub(crate) type OscAddressCollector = fn(&mut World) -> Vec<(Entity, Vec<OscAddress>)>;
+
+/// Type-erased access to the components of a registered [`OscMethod`] type
+#[derive(Clone, Copy)]
+pub(crate) struct OscRegisteredMethod {
+    /// Only read by the OSCQuery namespace
+    #[cfg_attr(not(feature = "oscquery"), allow(dead_code))]
+    pub(crate) collect: OscAddressCollector,
+}
+
+/// The [`OscMethod`] types that have a dispatcher system
+#[derive(Resource, Default)]
+pub(crate) struct OscRegisteredMethods(pub(crate) HashMap<TypeId, OscRegisteredMethod>);
+
+/// Extension methods for registering [`OscMethod`] components with an [`App`]
+///
+/// Add the [`BevyRoscPlugin`](crate::BevyRoscPlugin) first, so the dispatcher system ends up in
+/// the schedule the plugin receives in.
+pub trait OscAppExt {
+    /// Adds the [`method_dispatcher_system`] for `T` to [`OscSet::Dispatch`]
+    ///
+    /// Registering the same type again does nothing.
+    fn add_osc_method<T: OscMethod + Component<Mutability = Mutable>>(&mut self) -> &mut Self;
+
+    /// Same as [`add_osc_method`](Self::add_osc_method), but also registers `T` for reflection
+    fn add_reflected_osc_method<
+        T: OscMethod + Component<Mutability = Mutable> + GetTypeRegistration,
+    >(
+        &mut self,
+    ) -> &mut Self;
+
+    /// Adds the [`osc_receive_system`] for the transport component `T` to [`OscSet::Receive`] and
+    /// registers it in the [`OscTransportRegistry`], so packets can be sent through it by entity
+    ///
+    /// Registering the same type again does nothing.
+    fn add_osc_transport<T: OscTransport + Component<Mutability = Mutable>>(&mut self)
+        -> &mut Self;
+}
+
+impl OscAppExt for App {
+    fn add_osc_method<T: OscMethod + Component<Mutability = Mutable>>(&mut self) -> &mut Self {
+        let mut registered = self
+            .world_mut()
+            .get_resource_or_init::<OscRegisteredMethods>();
+        if registered.0.contains_key(&TypeId::of::<T>()) {
+            return self;
+        }
+        registered.0.insert(
+            TypeId::of::<T>(),
+            OscRegisteredMethod {
+                collect: |world| {
+                    world
+                        .query::<(Entity, &T)>()
+                        .iter(world)
+                        .map(|(entity, osc_method)| (entity, osc_method.get_addresses()))
+                        .collect()
+                },
+            },
+        );
+
+        let schedule = self
+            .world()
+            .get_resource::<OscSchedules>()
+            .copied()
+            .unwrap_or_default()
+            .receive;
+        self.add_systems(
+            schedule,
+            method_dispatcher_system::<T>.in_set(OscSet::Dispatch),
+        )
+    }
+
+    fn add_reflected_osc_method<
+        T: OscMethod + Component<Mutability = Mutable> + GetTypeRegistration,
+    >(
+        &mut self,
+    ) -> &mut Self {
+        self.register_type::<T>().add_osc_method::<T>()
+    }
+
+    fn add_osc_transport<T: OscTransport + Component<Mutability = Mutable>>(
+        &mut self,
+    ) -> &mut Self {
+        let mut registry = self
+            .world_mut()
+            .get_resource_or_init::<OscTransportRegistry>();
+        if !registry.register::<T>() {
+            return self;
+        }
+
+        let schedule = receive_schedule(self);
+        self.add_systems(schedule, osc_receive_system::<T>.in_set(OscSet::Receive))
+    }
+}
+
+/// The schedule the plugin receives in, or the default one if the plugin wasn't added
+pub(crate) fn receive_schedule(app: &App) -> InternedScheduleLabel {
+    app.world()
+        .get_resource::<OscSchedules>()
+        .copied()
+        .unwrap_or_default()
+        .receive
+}
@@ -0,0 +1,305 @@
+use rosc::{OscColor, OscMidiMessage, OscTime, OscType};
+use std::fmt;
+
+/// Errors that can occur when decoding OSC arguments with [`OscArgs`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OscArgsError {
+    /// A required argument is missing
+    Missing {
+        index: usize,
+        /// Name of the expected type
+        expected: &'static str,
+    },
+    /// An argument has a type that can't be converted to the expected one
+    Mismatch {
+        index: usize,
+        /// Name of the expected type
+        expected: &'static str,
+        /// OSC type tag of the received argument
+        found: char,
+    },
+    /// There are more arguments than expected
+    TooMany { expected: usize, found: usize },
+}
+
+impl OscArgsError {
+    /// Fails with [`TooMany`](Self::TooMany) if there are more than `expected` arguments
+    pub fn check_count(args: &[OscType], expected: usize) -> Result<(), OscArgsError> {
+        if args.len() > expected {
+            return Err(OscArgsError::TooMany {
+                expected,
+                found: args.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for OscArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OscArgsError::Missing { index, expected } => {
+                write!(f, "Missing argument {}, expected {}", index, expected)
+            }
+            OscArgsError::Mismatch {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Argument {} has type tag '{}', expected {}",
+                index, found, expected
+            ),
+            OscArgsError::TooMany { expected, found } => write!(
+                f,
+                "Expected at most {} arguments, found {}",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for OscArgsError {}
+
+/// Returns the OSC type tag of an argument
+pub fn osc_type_tag(arg: &OscType) -> char {
+    match arg {
+        OscType::Int(_) => 'i',
+        OscType::Float(_) => 'f',
+        OscType::String(_) => 's',
+        OscType::Blob(_) => 'b',
+        OscType::Time(_) => 't',
+        OscType::Long(_) => 'h',
+        OscType::Double(_) => 'd',
+        OscType::Char(_) => 'c',
+        OscType::Color(_) => 'r',
+        OscType::Midi(_) => 'm',
+        OscType::Bool(true) => 'T',
+        OscType::Bool(false) => 'F',
+        OscType::Array(_) => '[',
+        OscType::Nil => 'N',
+        OscType::Inf => 'I',
+    }
+}
+
+/// A sequence of OSC arguments that can be decoded from and encoded into a message's `args`
+///
+/// Implemented for tuples of [`OscArg`]s, and for structs with `#[derive(OscArgs)]`:
+///
+/// ```
+/// use bevy_rosc::OscArgs;
+///
+/// #[derive(OscArgs, Debug, PartialEq)]
+/// struct Note {
+///     pitch: i32,
+///     velocity: f32,
+///     channel: Option<i32>,
+/// }
+///
+/// let note = Note::decode_args(&[60.into(), 1.into()]).unwrap();
+/// assert_eq!(Note { pitch: 60, velocity: 1.0, channel: None }, note);
+/// ```
+///
+/// Optional fields have to come last:
+///
+/// ```compile_fail
+/// use bevy_rosc::OscArgs;
+///
+/// #[derive(OscArgs)]
+/// struct Note {
+///     channel: Option<i32>,
+///     pitch: i32,
+/// }
+/// ```
+///
+/// The same applies to tuples, and to type parameters that are `Option`s:
+///
+/// ```compile_fail
+/// use bevy_rosc::OscArgs;
+///
+/// let note = <(Option<i32>, i32)>::decode_args(&[]);
+/// ```
+///
+/// ```compile_fail
+/// use bevy_rosc::OscArgs;
+///
+/// #[derive(OscArgs)]
+/// struct Note<T> {
+///     channel: T,
+///     pitch: i32,
+/// }
+///
+/// let note = Note::<Option<i32>>::decode_args(&[]);
+/// ```
+pub trait OscArgs: Sized {
+    /// Decode the arguments, converting integers where floating point numbers are expected
+    fn decode_args(args: &[OscType]) -> Result<Self, OscArgsError>;
+
+    /// Encode into OSC arguments
+    fn encode_args(&self) -> Vec<OscType>;
+}
+
+/// A single OSC argument, see [`OscArgs`]
+pub trait OscArg: Sized {
+    /// Whether the argument may be left out. Optional arguments can only be followed by other
+    /// optional arguments.
+    const OPTIONAL: bool = false;
+
+    /// Decode the argument at `index`
+    fn decode_arg(args: &[OscType], index: usize) -> Result<Self, OscArgsError>;
+
+    /// Append the encoded argument to `args`
+    fn encode_arg(&self, args: &mut Vec<OscType>);
+}
+
+/// Trailing arguments that may be left out, `None` is not encoded at all
+impl<T: OscArg> OscArg for Option<T> {
+    const OPTIONAL: bool = true;
+
+    fn decode_arg(args: &[OscType], index: usize) -> Result<Self, OscArgsError> {
+        if index >= args.len() {
+            return Ok(None);
+        }
+        T::decode_arg(args, index).map(Some)
+    }
+
+    fn encode_arg(&self, args: &mut Vec<OscType>) {
+        if let Some(arg) = self {
+            arg.encode_arg(args);
+        }
+    }
+}
+
+macro_rules! osc_arg_impl {
+    ($ty:ty, $expected:literal, |$arg:ident| $decode:expr) => {
+        osc_arg_impl!($ty, $expected, |$arg| $decode, |value| value.clone().into());
+    };
+    ($ty:ty, $expected:literal, |$arg:ident| $decode:expr, |$value:ident| $encode:expr) => {
+        impl OscArg for $ty {
+            fn decode_arg(args: &[OscType], index: usize) -> Result<Self, OscArgsError> {
+                let Some($arg) = args.get(index) else {
+                    return Err(OscArgsError::Missing {
+                        index,
+                        expected: $expected,
+                    });
+                };
+                let decoded: Option<$ty> = $decode;
+                decoded.ok_or_else(|| OscArgsError::Mismatch {
+                    index,
+                    expected: $expected,
+                    found: osc_type_tag($arg),
+                })
+            }
+
+            fn encode_arg(&self, args: &mut Vec<OscType>) {
+                let $value = self;
+                args.push($encode);
+            }
+        }
+    };
+}
+
+osc_arg_impl!(i32, "int", |arg| match arg {
+    OscType::Int(i) => Some(*i),
+    _ => None,
+});
+osc_arg_impl!(i64, "long", |arg| match arg {
+    OscType::Int(i) => Some(*i as i64),
+    OscType::Long(l) => Some(*l),
+    _ => None,
+});
+osc_arg_impl!(f32, "float", |arg| match arg {
+    OscType::Float(f) => Some(*f),
+    OscType::Int(i) => Some(*i as f32),
+    _ => None,
+});
+osc_arg_impl!(f64, "double", |arg| match arg {
+    OscType::Double(d) => Some(*d),
+    OscType::Float(f) => Some(*f as f64),
+    OscType::Int(i) => Some(*i as f64),
+    OscType::Long(l) => Some(*l as f64),
+    _ => None,
+});
+osc_arg_impl!(bool, "bool", |arg| match arg {
+    OscType::Bool(b) => Some(*b),
+    _ => None,
+});
+osc_arg_impl!(char, "char", |arg| match arg {
+    OscType::Char(c) => Some(*c),
+    _ => None,
+});
+osc_arg_impl!(String, "string", |arg| match arg {
+    OscType::String(s) => Some(s.clone()),
+    _ => None,
+});
+osc_arg_impl!(Vec<u8>, "blob", |arg| match arg {
+    OscType::Blob(b) => Some(b.clone()),
+    _ => None,
+});
+osc_arg_impl!(
+    OscTime,
+    "time",
+    |arg| match arg {
+        OscType::Time(time) => Some(*time),
+        _ => None,
+    },
+    |time| OscType::Time(*time)
+);
+osc_arg_impl!(OscColor, "color", |arg| match arg {
+    OscType::Color(color) => Some(color.clone()),
+    _ => None,
+});
+osc_arg_impl!(OscMidiMessage, "midi", |arg| match arg {
+    OscType::Midi(midi) => Some(midi.clone()),
+    _ => None,
+});
+osc_arg_impl!(OscType, "any", |arg| Some(arg.clone()));
+
+/// Returns false if a required argument follows an optional one, see [`OscArg::OPTIONAL`]
+///
+/// Used to check [`OscArgs`] implementations at compile time.
+#[doc(hidden)]
+pub const fn optional_args_last(optional: &[bool]) -> bool {
+    let mut index = 1;
+    while index < optional.len() {
+        if optional[index - 1] && !optional[index] {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+macro_rules! osc_args_tuple_impl {
+    ($count:literal; $($name:ident $index:tt),*) => {
+        impl<$($name: OscArg),*> OscArgs for ($($name,)*) {
+            fn decode_args(args: &[OscType]) -> Result<Self, OscArgsError> {
+                const {
+                    assert!(
+                        optional_args_last(&[$($name::OPTIONAL),*]),
+                        "Required arguments can't follow optional arguments"
+                    )
+                };
+                OscArgsError::check_count(args, $count)?;
+                Ok(($($name::decode_arg(args, $index)?,)*))
+            }
+
+            #[allow(unused_mut)]
+            fn encode_args(&self) -> Vec<OscType> {
+                let mut args = Vec::with_capacity($count);
+                $(self.$index.encode_arg(&mut args);)*
+                args
+            }
+        }
+    };
+}
+
+osc_args_tuple_impl!(0;);
+osc_args_tuple_impl!(1; A 0);
+osc_args_tuple_impl!(2; A 0, B 1);
+osc_args_tuple_impl!(3; A 0, B 1, C 2);
+osc_args_tuple_impl!(4; A 0, B 1, C 2, D 3);
+osc_args_tuple_impl!(5; A 0, B 1, C 2, D 3, E 4);
+osc_args_tuple_impl!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+osc_args_tuple_impl!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+osc_args_tuple_impl!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
@@ -0,0 +1,344 @@
+use crate::{osc_type_tag, OscErrorEvent, OscMessageMeta, OscMethod};
+use bevy::ecs::reflect::ReflectComponent;
+use bevy::prelude::*;
+use bevy::reflect::{
+    ParsedPath, PartialReflect, ReflectMut, ReflectPath, ReflectRef, TypeRegistration, TypeRegistry,
+};
+use rosc::address::{Matcher, OscAddress};
+use rosc::{OscError, OscMessage, OscType};
+use std::collections::VecDeque;
+use std::fmt;
+
+/// Errors that can occur when writing a message's arguments into a field with [`OscBind`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OscBindError {
+    /// The target path could not be parsed
+    InvalidPath(String),
+    /// No component with the target's type name is registered for reflection, or it doesn't
+    /// reflect `Component`
+    UnknownComponent(String),
+    /// The entity doesn't have the target component
+    MissingComponent(String),
+    /// The field path doesn't exist on the component
+    MissingField(String),
+    /// The field has a type that can't be converted to OSC arguments
+    Unsupported(String),
+    /// The arguments can't be converted to the type of the field
+    Mismatch {
+        /// Type path of the field
+        expected: String,
+        /// OSC type tags of the received arguments
+        found: String,
+    },
+}
+
+impl fmt::Display for OscBindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OscBindError::InvalidPath(e) => write!(f, "Invalid target path: {}", e),
+            OscBindError::UnknownComponent(name) => {
+                write!(f, "Component {} is not registered for reflection", name)
+            }
+            OscBindError::MissingComponent(name) => {
+                write!(f, "Entity has no {} component", name)
+            }
+            OscBindError::MissingField(e) => write!(f, "Field not found: {}", e),
+            OscBindError::Unsupported(type_path) => {
+                write!(f, "Can't convert {} to OSC arguments", type_path)
+            }
+            OscBindError::Mismatch { expected, found } => {
+                write!(f, "Can't write arguments ',{}' into {}", found, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for OscBindError {}
+
+/// A field of a component, written as `Component.field.path`, e.g. `Transform.translation.x`
+///
+/// The component is identified by its short type path, or its full type path if the short one is
+/// ambiguous. The field path uses the syntax of [`ParsedPath`] and may be empty to target the
+/// whole component.
+#[derive(Clone, Debug)]
+pub struct OscBindTarget {
+    component: String,
+    pub(crate) path: ParsedPath,
+}
+
+impl OscBindTarget {
+    /// Parse a target like `Transform.translation.x`
+    pub fn parse(target: &str) -> Result<Self, OscBindError> {
+        let (component, path) = target.split_once('.').unwrap_or((target, ""));
+        Ok(Self {
+            component: component.to_string(),
+            path: ParsedPath::parse(path).map_err(|e| OscBindError::InvalidPath(e.to_string()))?,
+        })
+    }
+
+    /// Returns the type path of the targeted component
+    pub fn component(&self) -> &str {
+        &self.component
+    }
+
+    /// Look up the registration of the targeted component, which has to reflect `Component`
+    pub(crate) fn registration<'a>(
+        &self,
+        registry: &'a TypeRegistry,
+    ) -> Result<&'a TypeRegistration, OscBindError> {
+        registry
+            .get_with_short_type_path(&self.component)
+            .or_else(|| registry.get_with_type_path(&self.component))
+            .filter(|registration| registration.data::<ReflectComponent>().is_some())
+            .ok_or_else(|| OscBindError::UnknownComponent(self.component.clone()))
+    }
+}
+
+/// Bevy component that writes the arguments of messages received at its address into a field of
+/// another component on the same entity using reflection
+///
+/// A single argument is converted to the field's type. Any number can be written into a float
+/// field, integer fields only take whole numbers within their range. Several arguments are written
+/// into the fields of a struct, tuple or array in order, e.g. three floats into a `Vec3`. Failures are reported as [`OscErrorEvent::Binding`].
+///
+/// The target component has to be registered for reflection and reflect `Component`.
+///
+/// ```
+/// # use bevy_rosc::{OscBind, OscBindTarget};
+/// let bind = OscBind::new(
+///     "/cube/pos/x".into(),
+///     OscBindTarget::parse("Transform.translation.x").unwrap(),
+/// )
+/// .unwrap();
+/// ```
+#[derive(Component)]
+pub struct OscBind {
+    address: OscAddress,
+    target: OscBindTarget,
+    /// Received messages that have not been written yet
+    messages: VecDeque<(OscMessage, OscMessageMeta)>,
+}
+
+impl OscBind {
+    /// Returns a new `OscBind`
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [BadAddress](rosc::OscError::BadAddress) error when the address is invalid.
+    pub fn new(address: String, target: OscBindTarget) -> Result<Self, OscError> {
+        Ok(Self {
+            address: OscAddress::new(address)?,
+            target,
+            messages: Default::default(),
+        })
+    }
+
+    /// Returns the field messages are written into
+    pub fn target(&self) -> &OscBindTarget {
+        &self.target
+    }
+}
+
+impl OscMethod for OscBind {
+    fn get_addresses(&self) -> Vec<OscAddress> {
+        vec![self.address.clone()]
+    }
+    fn receive_message(&mut self, osc_message: OscMessage) {
+        self.receive_message_with_meta(osc_message, &OscMessageMeta::now())
+    }
+    fn receive_message_with_meta(&mut self, osc_message: OscMessage, meta: &OscMessageMeta) {
+        self.messages.push_back((osc_message, meta.clone()))
+    }
+    fn match_message_with_meta(
+        &mut self,
+        matcher: &Matcher,
+        message: &OscMessage,
+        meta: &OscMessageMeta,
+    ) -> bool {
+        self.match_addresses_with_meta(matcher, message, meta)
+    }
+}
+
+/// Writes the messages received by all [`OscBind`]s into their target fields
+///
+/// The plugin adds this system to [`OscSet::Dispatch`](crate::OscSet::Dispatch).
+pub fn osc_bind_system(world: &mut World) {
+    let mut query = world.query::<(Entity, &mut OscBind)>();
+    let mut pending = vec![];
+    for (entity, mut bind) in query.iter_mut(world) {
+        if !bind.messages.is_empty() {
+            let messages: Vec<_> = bind.messages.drain(..).collect();
+            pending.push((entity, bind.target.clone(), messages));
+        }
+    }
+    if pending.is_empty() {
+        return;
+    }
+
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    for (entity, target, messages) in pending {
+        for (message, meta) in messages {
+            if let Err(error) = write_message(world, &registry, entity, &target, &message.args) {
+                world.send_event(OscErrorEvent::Binding {
+                    entity,
+                    address: message.addr,
+                    meta,
+                    error,
+                });
+            }
+        }
+    }
+}
+
+/// Write the arguments of a message into the target field of `entity`
+fn write_message(
+    world: &mut World,
+    registry: &TypeRegistry,
+    entity: Entity,
+    target: &OscBindTarget,
+    args: &[OscType],
+) -> Result<(), OscBindError> {
+    let reflect_component = target
+        .registration(registry)?
+        .data::<ReflectComponent>()
+        .unwrap();
+
+    let mut entity_mut = world.entity_mut(entity);
+    let Some(mut component) = reflect_component.reflect_mut(&mut entity_mut) else {
+        return Err(OscBindError::MissingComponent(target.component.clone()));
+    };
+    let field = target
+        .path
+        .reflect_element_mut(component.as_partial_reflect_mut())
+        .map_err(|e| OscBindError::MissingField(e.to_string()))?;
+    write_args(field, args)
+}
+
+/// Write `args` into `field`, converting them as needed
+///
+/// Several arguments are written into the elements of a struct, tuple or list. They are only
+/// written if all of them fit, so a mismatch never leaves the field partially written.
+fn write_args(field: &mut dyn PartialReflect, args: &[OscType]) -> Result<(), OscBindError> {
+    if let [arg] = args {
+        if write_value(field, arg) {
+            return Ok(());
+        }
+    }
+
+    let fits = element_count(field) == Some(args.len())
+        && args.iter().enumerate().all(|(i, arg)| {
+            element_mut(field, i).is_some_and(|element| accepts_value(element, arg))
+        });
+    if !fits {
+        return Err(OscBindError::Mismatch {
+            expected: field.reflect_type_path().to_string(),
+            found: args.iter().map(osc_type_tag).collect(),
+        });
+    }
+    for (i, arg) in args.iter().enumerate() {
+        write_value(element_mut(field, i).unwrap(), arg);
+    }
+    Ok(())
+}
+
+/// Returns the number of elements of a struct, tuple or list
+fn element_count(field: &dyn PartialReflect) -> Option<usize> {
+    match field.reflect_ref() {
+        ReflectRef::Struct(s) => Some(s.field_len()),
+        ReflectRef::TupleStruct(s) => Some(s.field_len()),
+        ReflectRef::Tuple(t) => Some(t.field_len()),
+        ReflectRef::Array(a) => Some(a.len()),
+        ReflectRef::List(l) => Some(l.len()),
+        _ => None,
+    }
+}
+
+/// Returns the element at `index` of a struct, tuple or list
+fn element_mut(field: &mut dyn PartialReflect, index: usize) -> Option<&mut dyn PartialReflect> {
+    match field.reflect_mut() {
+        ReflectMut::Struct(s) => s.field_at_mut(index),
+        ReflectMut::TupleStruct(s) => s.field_mut(index),
+        ReflectMut::Tuple(t) => t.field_mut(index),
+        ReflectMut::Array(a) => a.get_mut(index),
+        ReflectMut::List(l) => l.get_mut(index),
+        _ => None,
+    }
+}
+
+/// Returns true if `arg` can be written into `field`, without changing it
+fn accepts_value(field: &dyn PartialReflect, arg: &OscType) -> bool {
+    field
+        .reflect_clone()
+        .is_ok_and(|mut copy| write_value(copy.as_partial_reflect_mut(), arg))
+}
+
+/// Write a single argument into a primitive field, returns false if the types don't fit
+fn write_value(field: &mut dyn PartialReflect, arg: &OscType) -> bool {
+    match arg {
+        OscType::Int(i) => write_number(field, *i as f64, Some(*i as i64)),
+        OscType::Long(l) => write_number(field, *l as f64, Some(*l)),
+        OscType::Float(f) => write_number(field, *f as f64, None),
+        OscType::Double(d) => write_number(field, *d, None),
+        OscType::Bool(b) => write::<bool>(field, *b),
+        OscType::Char(c) => write::<char>(field, *c),
+        OscType::String(s) => write::<String>(field, s.clone()),
+        _ => false,
+    }
+}
+
+fn write<T: 'static>(field: &mut dyn PartialReflect, value: T) -> bool {
+    match field.try_downcast_mut::<T>() {
+        Some(field) => {
+            *field = value;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Write a number into any numeric field, integers are taken from `integer` if it's available to
+/// not lose precision
+///
+/// Integer fields only accept values within their range, floats also have to be finite and whole.
+fn write_number(field: &mut dyn PartialReflect, float: f64, integer: Option<i64>) -> bool {
+    macro_rules! write_float {
+        ($($ty:ty),*) => {
+            $(
+                if let Some(field) = field.try_downcast_mut::<$ty>() {
+                    *field = match integer {
+                        Some(integer) => integer as $ty,
+                        None => float as $ty,
+                    };
+                    return true;
+                }
+            )*
+        };
+    }
+    macro_rules! write_integer {
+        ($($ty:ty),*) => {
+            $(
+                if let Some(field) = field.try_downcast_mut::<$ty>() {
+                    let value = match integer {
+                        Some(integer) => <$ty>::try_from(integer).ok(),
+                        // NaN and infinity have no fraction of zero, `MAX as f64 + 1.0` is exact unlike `MAX as f64`
+                        None => (float.fract() == 0.0
+                            && float >= <$ty>::MIN as f64
+                            && float < <$ty>::MAX as f64 + 1.0)
+                            .then_some(float as $ty),
+                    };
+                    return match value {
+                        Some(value) => {
+                            *field = value;
+                            true
+                        }
+                        None => false,
+                    };
+                }
+            )*
+        };
+    }
+    write_float!(f32, f64);
+    write_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+    false
+}
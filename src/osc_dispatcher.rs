@@ -1,94 +1,498 @@
-use crate::OscMethod;
+use crate::osc_matcher_cache::OscMatcherCache;
+use crate::osc_received::{OscMatchedMessages, OscReceivedObserved};
+use crate::{
+    OscAddressSpace, OscErrorEvent, OscMatcherCacheStats, OscMessageMeta, OscMethod, OscReceived,
+    OscSet,
+};
 use bevy::ecs::component::Mutable;
+use bevy::ecs::schedule::ScheduleConfigs;
+use bevy::ecs::system::ScheduleSystem;
 use bevy::prelude::*;
 use rosc::address::Matcher;
-use rosc::{OscBundle, OscError, OscMessage, OscPacket};
-use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use rosc::{OscBundle, OscMessage, OscPacket, OscTime};
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The special timetag meaning "immediately"
+const IMMEDIATELY: OscTime = OscTime {
+    seconds: 0,
+    fractional: 1,
+};
+
+/// Seconds between the OSC epoch (1900) and the unix epoch (1970)
+const UNIX_OFFSET: u32 = 2_208_988_800;
+
+/// Default maximum number of bundles the [`OscDispatcher`] holds back at the same time
+pub const DEFAULT_MAX_SCHEDULED: usize = 1024;
+
+/// Default maximum time the [`OscDispatcher`] holds back a bundle, one minute
+pub const DEFAULT_MAX_SCHEDULE_HORIZON: Duration = Duration::from_secs(60);
+
+/// What the [`OscDispatcher`] does with bundles whose timetag has already passed when they are
+/// received
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OscLatePolicy {
+    /// Deliver the bundle's messages immediately
+    #[default]
+    Deliver,
+    /// Discard the bundle
+    Drop,
+    /// Deliver the bundle's messages immediately and set [`OscMessageMeta::late`]
+    Flag,
+}
+
+/// Reasons for the [`OscDispatcher`] to drop a bundle with a timetag in the future
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OscScheduleError {
+    /// The maximum number of scheduled bundles was reached
+    Full {
+        /// The maximum number of scheduled bundles
+        capacity: usize,
+    },
+    /// The timetag is further in the future than the maximum schedule horizon
+    TooFarAhead {
+        /// The maximum schedule horizon
+        horizon: Duration,
+    },
+}
+
+impl fmt::Display for OscScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OscScheduleError::Full { capacity } => {
+                write!(f, "Already holding back {} bundles", capacity)
+            }
+            OscScheduleError::TooFarAhead { horizon } => {
+                write!(f, "Timetag is more than {:?} in the future", horizon)
+            }
+        }
+    }
+}
+
+impl std::error::Error for OscScheduleError {}
+
+/// A bundle with a timetag in the future, waiting to be dispatched
+struct ScheduledBundle {
+    due: SystemTime,
+    bundle: OscBundle,
+    meta: OscMessageMeta,
+}
 
 /// Dispatches received [`OscPacket`](rosc::OscPacket)s and sends the [`OscDispatchEvent`], which ultimately delivers messages to matching [`OscMethod`](crate::OscMethod)s.
-#[derive(Default, Resource)]
+///
+/// Bundles with a timetag in the future are held back until their time has come. They are
+/// released by the first call to [`dispatch`](Self::dispatch),
+/// [`dispatch_received`](Self::dispatch_received) or [`dispatch_scheduled`](Self::dispatch_scheduled)
+/// after that, which the plugin does every frame.
+///
+/// Remote peers must not be able to grow the schedule indefinitely, so at most
+/// [`DEFAULT_MAX_SCHEDULED`] bundles are held back, for at most [`DEFAULT_MAX_SCHEDULE_HORIZON`].
+/// Other bundles are dropped and reported as [`OscErrorEvent::Schedule`].
+///
+/// The [`Matcher`]s created for received address patterns are kept in a least recently used
+/// cache, so senders using ever-changing addresses can't grow it indefinitely.
+#[derive(Resource)]
 pub struct OscDispatcher {
-    matchers: HashMap<String, Matcher>,
+    matchers: OscMatcherCache,
+    /// Future bundles, ordered by due time
+    scheduled: Vec<ScheduledBundle>,
+    max_scheduled: usize,
+    max_schedule_horizon: Duration,
+    late_policy: OscLatePolicy,
+}
+
+impl Default for OscDispatcher {
+    fn default() -> Self {
+        Self {
+            matchers: Default::default(),
+            scheduled: vec![],
+            max_scheduled: DEFAULT_MAX_SCHEDULED,
+            max_schedule_horizon: DEFAULT_MAX_SCHEDULE_HORIZON,
+            late_policy: Default::default(),
+        }
+    }
 }
 
 impl OscDispatcher {
+    /// Returns a new `OscDispatcher` that handles late bundles according to `late_policy`
+    pub fn with_late_policy(late_policy: OscLatePolicy) -> Self {
+        Self {
+            late_policy,
+            ..Default::default()
+        }
+    }
+
+    /// Returns how bundles are handled whose timetag has already passed when they are received
+    pub fn late_policy(&self) -> OscLatePolicy {
+        self.late_policy
+    }
+
+    /// Sets how bundles are handled whose timetag has already passed when they are received
+    pub fn set_late_policy(&mut self, late_policy: OscLatePolicy) {
+        self.late_policy = late_policy;
+    }
+
+    /// Returns a new `OscDispatcher` that caches at most `capacity` address pattern matchers
+    pub fn with_matcher_cache_capacity(capacity: usize) -> Self {
+        Self {
+            matchers: OscMatcherCache::new(capacity),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the maximum number of cached address pattern matchers
+    ///
+    /// A capacity of 0 disables caching.
+    pub fn set_matcher_cache_capacity(&mut self, capacity: usize) {
+        self.matchers.set_capacity(capacity);
+    }
+
+    /// Returns hit and miss statistics of the address pattern matcher cache
+    pub fn matcher_cache_stats(&self) -> OscMatcherCacheStats {
+        self.matchers.stats()
+    }
+
+    /// Returns the number of bundles waiting for their timetag
+    pub fn scheduled_len(&self) -> usize {
+        self.scheduled.len()
+    }
+
+    /// Returns the maximum number of bundles waiting for their timetag
+    pub fn max_scheduled(&self) -> usize {
+        self.max_scheduled
+    }
+
+    /// Sets the maximum number of bundles waiting for their timetag
+    ///
+    /// Bundles that are already scheduled are kept.
+    pub fn set_max_scheduled(&mut self, max_scheduled: usize) {
+        self.max_scheduled = max_scheduled;
+    }
+
+    /// Returns how far in the future the timetag of a held back bundle may be
+    pub fn max_schedule_horizon(&self) -> Duration {
+        self.max_schedule_horizon
+    }
+
+    /// Sets how far in the future the timetag of a held back bundle may be
+    pub fn set_max_schedule_horizon(&mut self, horizon: Duration) {
+        self.max_schedule_horizon = horizon;
+    }
+
     /// Dispatch [`OscPacket`](rosc::OscPacket)s to [`OscDispatchEvent`]
     ///
     /// This method unpacks all [`OscPacket`](rosc::OscPacket)s into their individual messages, and
     /// then sends them on to be dispatched in the [`OscDispatchEvent`].
+    ///
+    /// The messages' [`OscMessageMeta`] will not have a source or server. Use
+    /// [`dispatch_received`](Self::dispatch_received) if you know where the packets came from.
+    ///
+    /// Messages with an invalid address pattern are skipped, an [`OscErrorEvent`] for each of
+    /// them is returned.
     pub fn dispatch(
         &mut self,
         osc_packets: Vec<OscPacket>,
         event_writer: EventWriter<OscDispatchEvent>,
+    ) -> Vec<OscErrorEvent> {
+        let meta = OscMessageMeta::now();
+        self.dispatch_received(
+            osc_packets
+                .into_iter()
+                .map(|osc_packet| (osc_packet, meta.clone()))
+                .collect(),
+            event_writer,
+        )
+    }
+
+    /// Same as [`dispatch`](Self::dispatch), but with metadata about how each packet was received
+    ///
+    /// The metadata is attached to all messages contained in the packet, with the
+    /// [`timetag`](OscMessageMeta::timetag) set to the one of the enclosing bundle.
+    pub fn dispatch_received(
+        &mut self,
+        osc_packets: Vec<(OscPacket, OscMessageMeta)>,
+        event_writer: EventWriter<OscDispatchEvent>,
+    ) -> Vec<OscErrorEvent> {
+        let now = SystemTime::now();
+        let mut errors = vec![];
+        let mut osc_messages = self.release_scheduled(now, &mut errors);
+
+        for (osc_packet, meta) in osc_packets {
+            match osc_packet {
+                OscPacket::Message(message) => osc_messages.push((message, meta)),
+                OscPacket::Bundle(bundle) => {
+                    self.unpack_bundle(bundle, meta, now, false, &mut osc_messages, &mut errors)
+                }
+            }
+        }
+        errors.extend(self.dispatch_messages(osc_messages, event_writer));
+        errors
+    }
+
+    /// Dispatch all scheduled bundles whose timetag has been reached
+    pub fn dispatch_scheduled(
+        &mut self,
+        event_writer: EventWriter<OscDispatchEvent>,
+    ) -> Vec<OscErrorEvent> {
+        self.dispatch_received(vec![], event_writer)
+    }
+
+    /// Take all bundles that are due from the schedule and unpack them
+    fn release_scheduled(
+        &mut self,
+        now: SystemTime,
+        errors: &mut Vec<OscErrorEvent>,
+    ) -> Vec<(OscMessage, OscMessageMeta)> {
+        let due_count = self
+            .scheduled
+            .partition_point(|scheduled| scheduled.due <= now);
+        let due: Vec<ScheduledBundle> = self.scheduled.drain(..due_count).collect();
+
+        let mut osc_messages = vec![];
+        for scheduled in due {
+            self.unpack_bundle(
+                scheduled.bundle,
+                scheduled.meta,
+                now,
+                true,
+                &mut osc_messages,
+                errors,
+            );
+        }
+        osc_messages
+    }
+
+    /// Hold back a bundle until `due`, unless the schedule is full or `due` is too far ahead
+    fn schedule(
+        &mut self,
+        due: SystemTime,
+        now: SystemTime,
+        bundle: OscBundle,
+        meta: OscMessageMeta,
+        errors: &mut Vec<OscErrorEvent>,
     ) {
-        let osc_messages = osc_packets
-            .into_iter()
-            .flat_map(|osc_packet| match osc_packet {
-                OscPacket::Message(message) => vec![message],
-                OscPacket::Bundle(bundle) => OscDispatcher::unpack_bundle(bundle),
+        let error = if self.scheduled.len() >= self.max_scheduled {
+            Some(OscScheduleError::Full {
+                capacity: self.max_scheduled,
             })
-            .collect();
-        let _ = self.dispatch_messages(osc_messages, event_writer);
+        } else if due.duration_since(now).unwrap_or_default() > self.max_schedule_horizon {
+            Some(OscScheduleError::TooFarAhead {
+                horizon: self.max_schedule_horizon,
+            })
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            errors.push(OscErrorEvent::Schedule {
+                timetag: bundle.timetag,
+                meta,
+                error,
+            });
+            return;
+        }
+
+        // Bundles with the same due time stay in the order they were received
+        let index = self
+            .scheduled
+            .partition_point(|scheduled| scheduled.due <= due);
+        self.scheduled
+            .insert(index, ScheduledBundle { due, bundle, meta });
     }
 
     fn dispatch_messages(
         &mut self,
-        osc_messages: Vec<OscMessage>,
+        osc_messages: Vec<(OscMessage, OscMessageMeta)>,
         mut event_writer: EventWriter<OscDispatchEvent>,
-    ) -> Result<(), OscError> {
+    ) -> Vec<OscErrorEvent> {
         let mut messages = vec![];
+        let mut errors = vec![];
 
-        for osc_message in &osc_messages {
-            let matcher = match self.matchers.entry(String::from(osc_message.addr.as_str())) {
-                // Create matchers for address patterns if they don't yet exist
-                Entry::Vacant(o) => o.insert(Matcher::new(osc_message.addr.as_str())?).clone(),
-                Entry::Occupied(o) => o.get().clone(),
+        for (osc_message, meta) in osc_messages {
+            let matcher = match self.matchers.get(osc_message.addr.as_str()) {
+                Ok(matcher) => matcher,
+                Err(error) => {
+                    errors.push(OscErrorEvent::Dispatch {
+                        address: osc_message.addr,
+                        meta,
+                        error,
+                    });
+                    continue;
+                }
             };
-            messages.push((matcher, osc_message.clone()))
+            messages.push((matcher, osc_message, meta))
         }
 
-        event_writer.write(OscDispatchEvent { messages });
+        // Transports are polled every frame, most of the time without receiving anything
+        if !messages.is_empty() {
+            event_writer.write(OscDispatchEvent { messages });
+        }
 
-        Ok(())
+        errors
     }
 
-    /// Recursively retrieve all `OscMessage`s from an `OscBundle`
-    fn unpack_bundle(osc_bundle: OscBundle) -> Vec<OscMessage> {
-        let mut messages = Vec::new();
+    /// Recursively retrieve all `OscMessage`s from an `OscBundle` that are due at `now`
+    ///
+    /// Bundles with a timetag after `now` are scheduled instead. `released` is true if the bundle
+    /// was already held back by the schedule, in which case it is never late.
+    fn unpack_bundle(
+        &mut self,
+        osc_bundle: OscBundle,
+        mut meta: OscMessageMeta,
+        now: SystemTime,
+        released: bool,
+        messages: &mut Vec<(OscMessage, OscMessageMeta)>,
+        errors: &mut Vec<OscErrorEvent>,
+    ) {
+        if let Some(due) = OscDispatcher::due_time(osc_bundle.timetag) {
+            if due > now {
+                self.schedule(due, now, osc_bundle, meta, errors);
+                return;
+            }
+            if !released && due < now {
+                match self.late_policy {
+                    OscLatePolicy::Deliver => {}
+                    OscLatePolicy::Drop => return,
+                    OscLatePolicy::Flag => meta.late = true,
+                }
+            }
+        }
+        meta.timetag = Some(osc_bundle.timetag);
 
         for osc_packet in osc_bundle.content {
             match osc_packet {
-                OscPacket::Message(message) => messages.push(message),
-                OscPacket::Bundle(bundle) => messages.extend(OscDispatcher::unpack_bundle(bundle)),
+                OscPacket::Message(message) => messages.push((message, meta.clone())),
+                OscPacket::Bundle(bundle) => {
+                    self.unpack_bundle(bundle, meta.clone(), now, released, messages, errors)
+                }
             }
         }
+    }
 
-        messages
+    /// Returns when a bundle with the given timetag is due, or `None` if it is due immediately
+    fn due_time(timetag: OscTime) -> Option<SystemTime> {
+        if timetag == IMMEDIATELY {
+            None
+        } else if timetag.seconds < UNIX_OFFSET {
+            // Can't be represented as `SystemTime`, but is long gone anyway
+            Some(UNIX_EPOCH)
+        } else {
+            Some(timetag.into())
+        }
     }
 }
 
-/// An event containing all OSC messages that were received this frame and their corresponding
-/// [`rosc::address::Matcher`]s
+/// An event containing all OSC messages that were received this frame, their corresponding
+/// [`rosc::address::Matcher`]s and metadata about how they were received
 #[derive(Event)]
 pub struct OscDispatchEvent {
-    pub messages: Vec<(Matcher, OscMessage)>,
+    pub messages: Vec<(Matcher, OscMessage, OscMessageMeta)>,
 }
 
 /// This reads [`OscDispatchEvent`]s sent by the dispatcher and forwards the incoming messages
 /// to [`OscMethod`](crate::OscMethod)s
 ///
 /// This system must be added for each [`OscMethod`](crate::OscMethod) you intend to use, otherwise
-/// messages won't be dispatched to it.
+/// messages won't be dispatched to it. See [`osc_method_dispatcher`] to add it to the right set.
+///
+/// Candidate methods are looked up in the [`OscAddressSpace`] of `T`, which this system creates
+/// the first time it runs. Until it exists, every method is a candidate. Messages are passed to
+/// the candidates with [`OscMethod::match_message_with_meta`], so methods that override it behave
+/// the same either way.
+///
+/// Every message a method receives is also triggered as [`OscReceived`] on its entity, as soon as
+/// an observer for it was added, and recorded for the
+/// [`osc_unmatched_system`](crate::osc_unmatched_system).
 pub fn method_dispatcher_system<T: OscMethod + Component<Mutability = Mutable>>(
+    mut commands: Commands,
     mut event_reader: EventReader<OscDispatchEvent>,
-    mut osc_method_query: Query<&mut T>,
+    mut osc_method_query: Query<(Entity, &mut T)>,
+    address_space: Option<Res<OscAddressSpace<T>>>,
+    mut address_space_requested: Local<bool>,
+    observed: Option<Res<OscReceivedObserved>>,
+    matched_messages: Option<Res<OscMatchedMessages>>,
 ) {
-    for ev in event_reader.read() {
-        for mut osc_method in osc_method_query.iter_mut() {
-            for (matcher, message) in &ev.messages {
-                osc_method.match_message(matcher, message);
+    let observed = observed.is_some_and(|observed| observed.0);
+
+    let Some(address_space) = address_space else {
+        if !*address_space_requested {
+            commands.queue(OscAddressSpace::<T>::init);
+            *address_space_requested = true;
+        }
+
+        for (ev, id) in event_reader.read_with_id() {
+            let mut matched_indices = vec![];
+            for (entity, mut osc_method) in osc_method_query.iter_mut() {
+                // Only methods that received a message count as changed
+                let mut matched = false;
+                for (index, (matcher, message, meta)) in ev.messages.iter().enumerate() {
+                    if osc_method
+                        .bypass_change_detection()
+                        .match_message_with_meta(matcher, message, meta)
+                    {
+                        matched = true;
+                        matched_indices.push(index);
+                        if observed {
+                            trigger_received(&mut commands, entity, message, meta);
+                        }
+                    }
+                }
+                if matched {
+                    osc_method.set_changed();
+                }
+            }
+            if let Some(matched_messages) = &matched_messages {
+                matched_messages.insert(id, matched_indices);
+            }
+        }
+        return;
+    };
+
+    for (ev, id) in event_reader.read_with_id() {
+        let mut matched_indices = vec![];
+        for (index, (matcher, message, meta)) in ev.messages.iter().enumerate() {
+            for entity in address_space.matching(matcher) {
+                let Ok((_, mut osc_method)) = osc_method_query.get_mut(entity) else {
+                    continue;
+                };
+                // The index only pre-filters, the method may still reject the message
+                if osc_method
+                    .bypass_change_detection()
+                    .match_message_with_meta(matcher, message, meta)
+                {
+                    osc_method.set_changed();
+                    matched_indices.push(index);
+                    if observed {
+                        trigger_received(&mut commands, entity, message, meta);
+                    }
+                }
             }
         }
+        if let Some(matched_messages) = &matched_messages {
+            matched_messages.insert(id, matched_indices);
+        }
     }
 }
+
+fn trigger_received(
+    commands: &mut Commands,
+    entity: Entity,
+    message: &OscMessage,
+    meta: &OscMessageMeta,
+) {
+    commands.trigger_targets(
+        OscReceived {
+            message: message.clone(),
+            meta: meta.clone(),
+        },
+        entity,
+    );
+}
+
+/// Returns the [`method_dispatcher_system`] for `T`, placed in [`OscSet::Dispatch`]
+///
+/// Add it to the schedule the plugin receives in ([`OscSchedules::receive`](crate::OscSchedules::receive),
+/// [`PreUpdate`] by default), so `T` gets its messages in the same frame as the included methods.
+pub fn osc_method_dispatcher<T: OscMethod + Component<Mutability = Mutable>>(
+) -> ScheduleConfigs<ScheduleSystem> {
+    method_dispatcher_system::<T>.in_set(OscSet::Dispatch)
+}
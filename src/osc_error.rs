@@ -0,0 +1,137 @@
+use crate::{OscBindError, OscMessageMeta, OscScheduleError, OscSendTarget};
+use bevy::prelude::*;
+use rosc::{OscError, OscTime};
+use std::fmt;
+use std::io;
+use std::net::SocketAddr;
+
+/// Errors that can occur when receiving or sending OSC packets over a transport
+#[derive(Debug)]
+pub enum OscTransportError {
+    /// The packet could not be encoded
+    OscError(OscError),
+    /// Received bytes could not be decoded into an OSC packet
+    DecodeError {
+        error: OscError,
+        /// The offending bytes
+        bytes: Vec<u8>,
+        /// Address of the sender, if the transport provides one
+        source: Option<SocketAddr>,
+    },
+    /// The underlying socket reported an error
+    IoError(io::Error),
+}
+
+impl fmt::Display for OscTransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OscTransportError::OscError(e) => write!(f, "OSC error: {}", e),
+            OscTransportError::DecodeError {
+                error,
+                bytes,
+                source: Some(source),
+            } => write!(
+                f,
+                "Failed to decode {} bytes from {}: {}",
+                bytes.len(),
+                source,
+                error
+            ),
+            OscTransportError::DecodeError { error, bytes, .. } => {
+                write!(f, "Failed to decode {} bytes: {}", bytes.len(), error)
+            }
+            OscTransportError::IoError(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for OscTransportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OscTransportError::OscError(e) => Some(e),
+            OscTransportError::DecodeError { error, .. } => Some(error),
+            OscTransportError::IoError(e) => Some(e),
+        }
+    }
+}
+
+impl From<OscError> for OscTransportError {
+    fn from(e: OscError) -> Self {
+        OscTransportError::OscError(e)
+    }
+}
+
+impl From<io::Error> for OscTransportError {
+    fn from(e: io::Error) -> Self {
+        OscTransportError::IoError(e)
+    }
+}
+
+/// An event that is sent whenever opening sockets, or receiving, dispatching, binding, sending or
+/// replying to OSC packets fails
+///
+/// Failures only affect the offending packet or message, everything else is still dispatched.
+#[derive(Event, Debug)]
+pub enum OscErrorEvent {
+    /// A transport failed to receive a packet
+    Receive {
+        /// The transport entity that failed
+        server: Entity,
+        error: OscTransportError,
+    },
+    /// A message could not be dispatched because its address pattern is invalid
+    Dispatch {
+        /// The offending address pattern
+        address: String,
+        meta: OscMessageMeta,
+        error: OscError,
+    },
+    /// A bundle with a timetag in the future was dropped instead of being held back
+    Schedule {
+        /// The bundle's timetag
+        timetag: OscTime,
+        meta: OscMessageMeta,
+        error: OscScheduleError,
+    },
+    /// A socket requested by the [`BevyRoscPlugin`](crate::BevyRoscPlugin) could not be opened
+    Bind {
+        /// The address to listen on, or the target address of a client
+        addr: SocketAddr,
+        error: io::Error,
+    },
+    /// A packet could not be sent
+    Send {
+        /// The transport entity that failed
+        transport: Entity,
+        error: OscTransportError,
+    },
+    /// An [`OscSendEvent`](crate::OscSendEvent) could not be sent to a target that isn't a
+    /// transport entity
+    SendTo {
+        target: OscSendTarget,
+        error: OscTransportError,
+    },
+    /// An [`OscReplyEvent`](crate::OscReplyEvent) could not be sent
+    Reply {
+        /// Metadata of the message that was replied to
+        meta: OscMessageMeta,
+        error: OscTransportError,
+    },
+    /// An [`OscPublish`](crate::OscPublish) could not read one of its fields
+    Publish {
+        /// The entity of the `OscPublish`
+        entity: Entity,
+        /// The address the field is published at
+        address: String,
+        error: OscBindError,
+    },
+    /// An [`OscBind`](crate::OscBind) could not write a message into its target field
+    Binding {
+        /// The entity of the `OscBind`
+        entity: Entity,
+        /// The address of the message
+        address: String,
+        meta: OscMessageMeta,
+        error: OscBindError,
+    },
+}
@@ -0,0 +1,119 @@
+use crate::{OscMessageMeta, OscMethod};
+use bevy::ecs::system::SystemId;
+use bevy::prelude::*;
+use rosc::address::OscAddress;
+use rosc::{OscError, OscMessage};
+use std::collections::VecDeque;
+
+type OscHandlerFn = Box<dyn FnMut(&OscMessage, &mut EntityCommands) + Send + Sync>;
+
+/// What an [`OscHandler`] does with a received message
+enum OscHandlerAction {
+    Closure(OscHandlerFn),
+    System(SystemId<In<OscMessage>>),
+}
+
+/// Bevy component that reacts to messages with a closure or a one-shot system
+///
+/// A lightweight alternative to implementing [`OscMethod`] for a new component. The closure gets
+/// the [`EntityCommands`] of the handler's entity:
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_rosc::OscHandler;
+/// #[derive(Component)]
+/// struct Hidden;
+///
+/// let handler = OscHandler::new("/hide".into(), |_, entity| {
+///     entity.insert(Hidden);
+/// })
+/// .unwrap();
+/// ```
+///
+/// Messages are handled by [`osc_handler_system`].
+#[derive(Component)]
+pub struct OscHandler {
+    addresses: Vec<OscAddress>,
+    action: OscHandlerAction,
+    /// Received messages that have not been handled yet
+    messages: VecDeque<OscMessage>,
+}
+
+impl OscHandler {
+    /// Returns a new `OscHandler` that calls `handler` for every message received at `address`
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [BadAddress](rosc::OscError::BadAddress) error when the address is invalid.
+    pub fn new<F>(address: String, handler: F) -> Result<Self, OscError>
+    where
+        F: FnMut(&OscMessage, &mut EntityCommands) + Send + Sync + 'static,
+    {
+        Self::with_action(address, OscHandlerAction::Closure(Box::new(handler)))
+    }
+
+    /// Returns a new `OscHandler` that runs the one-shot system `system` with every message
+    /// received at `address`
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [BadAddress](rosc::OscError::BadAddress) error when the address is invalid.
+    pub fn from_system(
+        address: String,
+        system: SystemId<In<OscMessage>>,
+    ) -> Result<Self, OscError> {
+        Self::with_action(address, OscHandlerAction::System(system))
+    }
+
+    fn with_action(address: String, action: OscHandlerAction) -> Result<Self, OscError> {
+        Ok(Self {
+            addresses: vec![OscAddress::new(address)?],
+            action,
+            messages: Default::default(),
+        })
+    }
+
+    /// Also handle messages received at `address`
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [BadAddress](rosc::OscError::BadAddress) error when the address is invalid.
+    pub fn with_address(mut self, address: String) -> Result<Self, OscError> {
+        self.addresses.push(OscAddress::new(address)?);
+        Ok(self)
+    }
+}
+
+impl OscMethod for OscHandler {
+    fn get_addresses(&self) -> Vec<OscAddress> {
+        self.addresses.clone()
+    }
+    fn receive_message(&mut self, osc_message: OscMessage) {
+        self.messages.push_back(osc_message)
+    }
+    fn receive_message_with_meta(&mut self, osc_message: OscMessage, _meta: &OscMessageMeta) {
+        self.receive_message(osc_message)
+    }
+}
+
+/// Calls the closures or runs the systems of all [`OscHandler`]s that received messages
+///
+/// The plugin adds this system to [`OscSet::Dispatch`](crate::OscSet::Dispatch).
+pub fn osc_handler_system(mut commands: Commands, mut query: Query<(Entity, &mut OscHandler)>) {
+    for (entity, mut handler) in query.iter_mut() {
+        if handler.messages.is_empty() {
+            continue;
+        }
+        let OscHandler {
+            action, messages, ..
+        } = &mut *handler;
+        for message in messages.drain(..) {
+            match action {
+                OscHandlerAction::Closure(handler) => {
+                    handler(&message, &mut commands.entity(entity))
+                }
+                OscHandlerAction::System(system) => commands.run_system_with(*system, message),
+            }
+        }
+    }
+}
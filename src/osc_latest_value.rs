@@ -0,0 +1,176 @@
+use crate::{OscMessageMeta, OscMethod};
+use bevy::prelude::*;
+use rosc::address::{Matcher, OscAddress};
+use rosc::{OscError, OscMessage};
+use std::time::Instant;
+
+/// The latest message received at one address of an [`OscLatestValue`]
+struct Entry {
+    address: OscAddress,
+    /// `address` as a string, to look it up without allocating
+    path: String,
+    latest: Option<(OscMessage, OscMessageMeta)>,
+}
+
+/// Bevy component that only keeps the latest message received at each of its addresses
+///
+/// Meant for continuous controllers like faders, which send many messages per frame although only
+/// the newest value matters. The component is only changed when a message arrives, so
+/// `Query<&OscLatestValue, Changed<OscLatestValue>>` only matches entities with new values.
+///
+/// A message with an address pattern updates every address of the component that it matches.
+#[derive(Component)]
+pub struct OscLatestValue {
+    entries: Vec<Entry>,
+    dirty: bool,
+    last_changed: Option<Instant>,
+}
+
+impl OscLatestValue {
+    /// Returns a new `OscLatestValue` with a single address
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [BadAddress](rosc::OscError::BadAddress) error when the address is invalid.
+    pub fn new(address: String) -> Result<Self, OscError> {
+        Self::with_addresses(vec![address])
+    }
+
+    /// Returns a new `OscLatestValue` that keeps the latest message for each of `addresses`
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [BadAddress](rosc::OscError::BadAddress) error when any address is invalid.
+    pub fn with_addresses(addresses: Vec<String>) -> Result<Self, OscError> {
+        let entries = addresses
+            .into_iter()
+            .map(|path| {
+                Ok(Entry {
+                    address: OscAddress::new(path.clone())?,
+                    path,
+                    latest: None,
+                })
+            })
+            .collect::<Result<_, OscError>>()?;
+        Ok(Self {
+            entries,
+            dirty: false,
+            last_changed: None,
+        })
+    }
+
+    /// Returns the latest message received at any address
+    pub fn latest(&self) -> Option<&OscMessage> {
+        self.latest_with_meta().map(|(message, _)| message)
+    }
+
+    /// Returns the latest message received at any address, together with its metadata
+    pub fn latest_with_meta(&self) -> Option<&(OscMessage, OscMessageMeta)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| entry.latest.as_ref())
+            .max_by_key(|(_, meta)| meta.received_at)
+    }
+
+    /// Returns the latest message received at `address`
+    pub fn get(&self, address: &str) -> Option<&OscMessage> {
+        self.get_with_meta(address).map(|(message, _)| message)
+    }
+
+    /// Returns the latest message received at `address`, together with its metadata
+    pub fn get_with_meta(&self, address: &str) -> Option<&(OscMessage, OscMessageMeta)> {
+        self.entries
+            .iter()
+            .find(|entry| entry.path == address)
+            .and_then(|entry| entry.latest.as_ref())
+    }
+
+    /// Iterates over all addresses that received a message, and their latest message
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &OscMessage)> {
+        self.entries.iter().filter_map(|entry| {
+            entry
+                .latest
+                .as_ref()
+                .map(|(message, _)| (entry.path.as_str(), message))
+        })
+    }
+
+    /// Returns true if a message was received since the last call to [`read`](Self::read)
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Returns the latest message if it wasn't read yet, and marks it as read
+    pub fn read(&mut self) -> Option<&OscMessage> {
+        if !std::mem::take(&mut self.dirty) {
+            return None;
+        }
+        self.latest()
+    }
+
+    /// Returns when the latest message was received, or `None` if none was received yet
+    pub fn last_changed(&self) -> Option<Instant> {
+        self.last_changed
+    }
+
+    /// Returns the indices of all entries whose address matches
+    fn matching(&self, matcher: &Matcher) -> Vec<usize> {
+        (0..self.entries.len())
+            .filter(|i| matcher.match_address(&self.entries[*i].address))
+            .collect()
+    }
+
+    /// Stores the message in the entries at `matching`, returns false if there are none
+    fn store(&mut self, matching: Vec<usize>, message: &OscMessage, meta: &OscMessageMeta) -> bool {
+        if matching.is_empty() {
+            return false;
+        }
+        for index in matching {
+            self.entries[index].latest = Some((message.clone(), meta.clone()));
+        }
+        self.dirty = true;
+        self.last_changed = Some(meta.received_at);
+        true
+    }
+}
+
+impl OscMethod for OscLatestValue {
+    fn get_addresses(&self) -> Vec<OscAddress> {
+        self.entries
+            .iter()
+            .map(|entry| entry.address.clone())
+            .collect()
+    }
+    fn receive_message(&mut self, osc_message: OscMessage) {
+        self.receive_message_with_meta(osc_message, &OscMessageMeta::now())
+    }
+    fn receive_message_with_meta(&mut self, osc_message: OscMessage, meta: &OscMessageMeta) {
+        // The dispatcher goes through `match_message_with_meta`, this is only reached by direct
+        // calls. Literal addresses are the common case and don't need a matcher
+        let matching: Vec<usize> = match self
+            .entries
+            .iter()
+            .position(|entry| entry.path == osc_message.addr)
+        {
+            Some(index) => vec![index],
+            None if self.entries.len() == 1 => vec![0],
+            None => match Matcher::new(&osc_message.addr) {
+                Ok(matcher) => self.matching(&matcher),
+                Err(_) => return,
+            },
+        };
+        self.store(matching, &osc_message, meta);
+    }
+    fn match_message(&mut self, matcher: &Matcher, message: &OscMessage) -> bool {
+        self.match_message_with_meta(matcher, message, &OscMessageMeta::now())
+    }
+    fn match_message_with_meta(
+        &mut self,
+        matcher: &Matcher,
+        message: &OscMessage,
+        meta: &OscMessageMeta,
+    ) -> bool {
+        let matching = self.matching(matcher);
+        self.store(matching, message, meta)
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{OscReceivedPacket, OscTransport, OscTransportError};
+use bevy::prelude::*;
+use rosc::OscPacket;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+
+/// In-memory transport that receives every packet that is sent through it
+///
+/// Useful for feeding packets into the dispatcher from within the app, and for tests.
+#[derive(Component, Default)]
+pub struct OscLoopback {
+    packets: VecDeque<OscReceivedPacket>,
+}
+
+impl OscLoopback {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a packet to be received, as if it was sent by `source`
+    pub fn push(&mut self, packet: OscPacket, source: Option<SocketAddr>) {
+        self.packets
+            .push_back(OscReceivedPacket::new(packet, source))
+    }
+
+    /// Returns the number of packets waiting to be received
+    pub fn len(&self) -> usize {
+        self.packets.len()
+    }
+
+    /// Returns true if there are no packets waiting to be received
+    pub fn is_empty(&self) -> bool {
+        self.packets.is_empty()
+    }
+}
+
+impl OscTransport for OscLoopback {
+    fn recv_packet(&mut self) -> Result<Option<OscReceivedPacket>, OscTransportError> {
+        Ok(self.packets.pop_front())
+    }
+
+    fn send_packet(
+        &mut self,
+        packet: &OscPacket,
+        _target: Option<SocketAddr>,
+    ) -> Result<(), OscTransportError> {
+        self.push(packet.clone(), None);
+        Ok(())
+    }
+}
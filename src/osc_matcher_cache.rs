@@ -0,0 +1,103 @@
+use rosc::address::Matcher;
+use rosc::OscError;
+use std::collections::{BTreeMap, HashMap};
+
+/// Number of address pattern matchers that are cached by default
+pub const DEFAULT_MATCHER_CACHE_CAPACITY: usize = 1024;
+
+/// Hit and miss statistics of the [`OscDispatcher`](crate::OscDispatcher)'s matcher cache
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OscMatcherCacheStats {
+    /// Number of lookups that found a cached matcher
+    pub hits: u64,
+    /// Number of lookups that had to create a new matcher
+    pub misses: u64,
+    /// Number of matchers that were evicted to make room for new ones
+    pub evictions: u64,
+    /// Number of currently cached matchers
+    pub len: usize,
+    /// Maximum number of cached matchers
+    pub capacity: usize,
+}
+
+/// Least recently used cache of [`Matcher`]s, keyed by address pattern
+pub(crate) struct OscMatcherCache {
+    capacity: usize,
+    /// Matchers and the time they were last used
+    matchers: HashMap<String, (Matcher, u64)>,
+    /// Address patterns ordered by the time they were last used
+    last_used: BTreeMap<u64, String>,
+    clock: u64,
+    stats: OscMatcherCacheStats,
+}
+
+impl Default for OscMatcherCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_MATCHER_CACHE_CAPACITY)
+    }
+}
+
+impl OscMatcherCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            matchers: Default::default(),
+            last_used: Default::default(),
+            clock: 0,
+            stats: OscMatcherCacheStats {
+                capacity,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Returns the cached matcher for `pattern`, creating it if it isn't cached
+    pub(crate) fn get(&mut self, pattern: &str) -> Result<Matcher, OscError> {
+        self.clock += 1;
+
+        if let Some((matcher, last_used)) = self.matchers.get_mut(pattern) {
+            self.stats.hits += 1;
+            let key = self.last_used.remove(last_used).unwrap_or_default();
+            *last_used = self.clock;
+            self.last_used.insert(self.clock, key);
+            return Ok(matcher.clone());
+        }
+
+        self.stats.misses += 1;
+        let matcher = Matcher::new(pattern)?;
+        if self.capacity == 0 {
+            return Ok(matcher);
+        }
+        while self.matchers.len() >= self.capacity {
+            self.evict_oldest();
+        }
+        self.matchers
+            .insert(pattern.to_string(), (matcher.clone(), self.clock));
+        self.last_used.insert(self.clock, pattern.to_string());
+        self.stats.len = self.matchers.len();
+
+        Ok(matcher)
+    }
+
+    /// Changes the maximum number of cached matchers, evicting the least recently used ones if
+    /// there are too many
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.stats.capacity = capacity;
+        while self.matchers.len() > capacity {
+            self.evict_oldest();
+        }
+    }
+
+    pub(crate) fn stats(&self) -> OscMatcherCacheStats {
+        self.stats
+    }
+
+    fn evict_oldest(&mut self) {
+        if let Some((_, pattern)) = self.last_used.pop_first() {
+            self.matchers.remove(&pattern);
+            self.stats.evictions += 1;
+            self.stats.len = self.matchers.len();
+        }
+    }
+}
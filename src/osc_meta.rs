@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+use rosc::OscTime;
+use std::net::SocketAddr;
+use std::time::Instant;
+
+/// Information about how and when an OSC message was received
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscMessageMeta {
+    /// Address of the sender, if the transport provides one
+    ///
+    /// IPv4 peers of dual-stack sockets are reported with their IPv4 address.
+    pub source: Option<SocketAddr>,
+    /// The transport entity (e.g. an [`OscUdpServer`](crate::OscUdpServer)) that received the message
+    pub server: Option<Entity>,
+    /// When the packet containing the message was received
+    pub received_at: Instant,
+    /// Timetag of the innermost bundle enclosing the message, or `None` if it was sent on its own
+    pub timetag: Option<OscTime>,
+    /// True if the enclosing bundle's timetag had already passed when it was received and the
+    /// dispatcher's [`OscLatePolicy`](crate::OscLatePolicy) is set to flag late bundles
+    pub late: bool,
+}
+
+impl OscMessageMeta {
+    /// Metadata for a message that was received just now from an unknown source
+    pub fn now() -> Self {
+        Self {
+            source: None,
+            server: None,
+            received_at: Instant::now(),
+            timetag: None,
+            late: false,
+        }
+    }
+}
@@ -1,14 +1,39 @@
+use crate::osc_queue::OscQueue;
+use crate::{OscArgs, OscArgsError, OscMessageMeta, OscQueuePolicy};
 use bevy::prelude::*;
 use rosc::address::{Matcher, OscAddress};
 use rosc::{OscError, OscMessage};
-use std::collections::VecDeque;
 
 /// An OSC Method is capable of receiving OSC messages at one or multiple addresses.
+///
+/// [`method_dispatcher_system`](crate::method_dispatcher_system) indexes the addresses of each
+/// method when the component is inserted, and then passes messages with a matching address to
+/// [`match_message_with_meta`](Self::match_message_with_meta). If a method's addresses change,
+/// re-insert the component to update the index.
 pub trait OscMethod {
     /// Returns all the addresses of this OSC method
     fn get_addresses(&self) -> Vec<OscAddress>;
     /// Receive an OSC message and do something with it, like storing it in a receive queue
     fn receive_message(&mut self, osc_message: OscMessage);
+    /// Same as [`receive_message`](Self::receive_message), but also gets metadata about how the
+    /// message was received, like the sender's address. Calls `receive_message` by default.
+    ///
+    /// Only called if [`match_message_with_meta`](Self::match_message_with_meta) is overridden,
+    /// e.g. with [`match_addresses_with_meta`](Self::match_addresses_with_meta).
+    fn receive_message_with_meta(&mut self, osc_message: OscMessage, meta: &OscMessageMeta) {
+        let _ = meta;
+        self.receive_message(osc_message)
+    }
+    /// Returns how many received messages the method keeps until they are read.
+    /// [`Unbounded`](OscQueuePolicy::Unbounded) by default.
+    fn queue_policy(&self) -> OscQueuePolicy {
+        OscQueuePolicy::Unbounded
+    }
+    /// Returns the number of received messages that were dropped because of the
+    /// [`queue_policy`](Self::queue_policy). 0 by default.
+    fn dropped_messages(&self) -> u64 {
+        0
+    }
     /// Check if an OSC message's address pattern matches with the method's address and receive it
     /// if it does. Also returns true if it's a match, and false otherwise.
     ///
@@ -26,6 +51,38 @@ pub trait OscMethod {
         }
         false
     }
+    /// Same as [`match_message`](Self::match_message), but also gets metadata about how the
+    /// message was received. This is what
+    /// [`method_dispatcher_system`](crate::method_dispatcher_system) calls.
+    ///
+    /// Calls `match_message` by default, so the metadata is dropped. Override it with
+    /// [`match_addresses_with_meta`](Self::match_addresses_with_meta) to receive messages with
+    /// [`receive_message_with_meta`](Self::receive_message_with_meta) instead.
+    fn match_message_with_meta(
+        &mut self,
+        matcher: &Matcher,
+        message: &OscMessage,
+        meta: &OscMessageMeta,
+    ) -> bool {
+        let _ = meta;
+        self.match_message(matcher, message)
+    }
+    /// Same as the default [`match_message`](Self::match_message), but receives the message with
+    /// [`receive_message_with_meta`](Self::receive_message_with_meta)
+    fn match_addresses_with_meta(
+        &mut self,
+        matcher: &Matcher,
+        message: &OscMessage,
+        meta: &OscMessageMeta,
+    ) -> bool {
+        for addr in &self.get_addresses() {
+            if matcher.match_address(addr) {
+                self.receive_message_with_meta(message.clone(), meta);
+                return true;
+            }
+        }
+        false
+    }
 }
 
 /// Bevy component that can receive OSC messages at multiple addresses
@@ -34,13 +91,29 @@ pub struct MultiAddressOscMethod {
     /// Valid OSC addresses
     addresses: Vec<OscAddress>,
     /// Received OSC messages that matched one of the addresses
-    messages: VecDeque<OscMessage>,
+    messages: OscQueue<(OscMessage, OscMessageMeta)>,
 }
 
 impl MultiAddressOscMethod {
     /// Gets the oldest message from the message queue
     pub fn get_message(&mut self) -> Option<OscMessage> {
-        self.messages.pop_front()
+        self.get_message_with_meta().map(|(message, _)| message)
+    }
+
+    /// Gets the oldest message from the message queue, together with its metadata
+    pub fn get_message_with_meta(&mut self) -> Option<(OscMessage, OscMessageMeta)> {
+        self.messages.pop()
+    }
+
+    /// Bounds the message queue, which is unbounded by default
+    pub fn with_queue_policy(mut self, policy: OscQueuePolicy) -> Self {
+        self.set_queue_policy(policy);
+        self
+    }
+
+    /// Bounds the message queue, messages that exceed the new bound are dropped
+    pub fn set_queue_policy(&mut self, policy: OscQueuePolicy) {
+        self.messages.set_policy(policy);
     }
 
     /// Returns a new `MultiAddressOscMethod`
@@ -69,7 +142,24 @@ impl OscMethod for MultiAddressOscMethod {
         self.addresses.clone()
     }
     fn receive_message(&mut self, osc_message: OscMessage) {
-        self.messages.push_back(osc_message)
+        self.receive_message_with_meta(osc_message, &OscMessageMeta::now())
+    }
+    fn receive_message_with_meta(&mut self, osc_message: OscMessage, meta: &OscMessageMeta) {
+        self.messages.push((osc_message, meta.clone()))
+    }
+    fn match_message_with_meta(
+        &mut self,
+        matcher: &Matcher,
+        message: &OscMessage,
+        meta: &OscMessageMeta,
+    ) -> bool {
+        self.match_addresses_with_meta(matcher, message, meta)
+    }
+    fn queue_policy(&self) -> OscQueuePolicy {
+        self.messages.policy()
+    }
+    fn dropped_messages(&self) -> u64 {
+        self.messages.dropped()
     }
 }
 
@@ -79,13 +169,29 @@ pub struct SingleAddressOscMethod {
     /// Valid OSC address
     address: OscAddress,
     /// Received OSC messages that matched one of the addresses
-    messages: VecDeque<OscMessage>,
+    messages: OscQueue<(OscMessage, OscMessageMeta)>,
 }
 
 impl SingleAddressOscMethod {
     /// Gets the oldest message from the message queue
     pub fn get_message(&mut self) -> Option<OscMessage> {
-        self.messages.pop_front()
+        self.get_message_with_meta().map(|(message, _)| message)
+    }
+
+    /// Gets the oldest message from the message queue, together with its metadata
+    pub fn get_message_with_meta(&mut self) -> Option<(OscMessage, OscMessageMeta)> {
+        self.messages.pop()
+    }
+
+    /// Bounds the message queue, which is unbounded by default
+    pub fn with_queue_policy(mut self, policy: OscQueuePolicy) -> Self {
+        self.set_queue_policy(policy);
+        self
+    }
+
+    /// Bounds the message queue, messages that exceed the new bound are dropped
+    pub fn set_queue_policy(&mut self, policy: OscQueuePolicy) {
+        self.messages.set_policy(policy);
     }
 
     /// Returns a new `SingleAddressOscMethod`
@@ -115,6 +221,108 @@ impl OscMethod for SingleAddressOscMethod {
         vec![self.address.clone()]
     }
     fn receive_message(&mut self, osc_message: OscMessage) {
-        self.messages.push_back(osc_message)
+        self.receive_message_with_meta(osc_message, &OscMessageMeta::now())
+    }
+    fn receive_message_with_meta(&mut self, osc_message: OscMessage, meta: &OscMessageMeta) {
+        self.messages.push((osc_message, meta.clone()))
+    }
+    fn match_message_with_meta(
+        &mut self,
+        matcher: &Matcher,
+        message: &OscMessage,
+        meta: &OscMessageMeta,
+    ) -> bool {
+        self.match_addresses_with_meta(matcher, message, meta)
+    }
+    fn queue_policy(&self) -> OscQueuePolicy {
+        self.messages.policy()
+    }
+    fn dropped_messages(&self) -> u64 {
+        self.messages.dropped()
+    }
+}
+
+/// Bevy component that receives OSC messages at one address and decodes their arguments into `T`
+///
+/// Add it with [`add_osc_method::<TypedOscMethod<T>>`](crate::OscAppExt::add_osc_method) for
+/// every `T` you use.
+#[derive(Component)]
+pub struct TypedOscMethod<T: OscArgs + Send + Sync + 'static> {
+    /// Valid OSC address
+    address: OscAddress,
+    /// Decoded arguments of received messages
+    values: OscQueue<(T, OscMessageMeta)>,
+    /// Received messages whose arguments could not be decoded
+    errors: OscQueue<(OscMessage, OscArgsError)>,
+}
+
+impl<T: OscArgs + Send + Sync + 'static> TypedOscMethod<T> {
+    /// Returns a new `TypedOscMethod`
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [BadAddress](rosc::OscError::BadAddress) error when the address is invalid.
+    pub fn new(address: String) -> Result<Self, OscError> {
+        Ok(Self {
+            address: OscAddress::new(address)?,
+            values: Default::default(),
+            errors: Default::default(),
+        })
+    }
+
+    /// Gets the oldest decoded value from the queue
+    pub fn get_value(&mut self) -> Option<T> {
+        self.get_value_with_meta().map(|(value, _)| value)
+    }
+
+    /// Gets the oldest decoded value from the queue, together with its message's metadata
+    pub fn get_value_with_meta(&mut self) -> Option<(T, OscMessageMeta)> {
+        self.values.pop()
+    }
+
+    /// Gets the oldest message whose arguments could not be decoded, together with the reason
+    pub fn get_error(&mut self) -> Option<(OscMessage, OscArgsError)> {
+        self.errors.pop()
+    }
+
+    /// Bounds the value and error queues, which are unbounded by default
+    pub fn with_queue_policy(mut self, policy: OscQueuePolicy) -> Self {
+        self.set_queue_policy(policy);
+        self
+    }
+
+    /// Bounds the value and error queues, entries that exceed the new bound are dropped
+    pub fn set_queue_policy(&mut self, policy: OscQueuePolicy) {
+        self.values.set_policy(policy);
+        self.errors.set_policy(policy);
+    }
+}
+
+impl<T: OscArgs + Send + Sync + 'static> OscMethod for TypedOscMethod<T> {
+    fn get_addresses(&self) -> Vec<OscAddress> {
+        vec![self.address.clone()]
+    }
+    fn receive_message(&mut self, osc_message: OscMessage) {
+        self.receive_message_with_meta(osc_message, &OscMessageMeta::now())
+    }
+    fn receive_message_with_meta(&mut self, osc_message: OscMessage, meta: &OscMessageMeta) {
+        match T::decode_args(&osc_message.args) {
+            Ok(value) => self.values.push((value, meta.clone())),
+            Err(error) => self.errors.push((osc_message, error)),
+        }
+    }
+    fn match_message_with_meta(
+        &mut self,
+        matcher: &Matcher,
+        message: &OscMessage,
+        meta: &OscMessageMeta,
+    ) -> bool {
+        self.match_addresses_with_meta(matcher, message, meta)
+    }
+    fn queue_policy(&self) -> OscQueuePolicy {
+        self.values.policy()
+    }
+    fn dropped_messages(&self) -> u64 {
+        self.values.dropped() + self.errors.dropped()
     }
 }
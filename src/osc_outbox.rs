@@ -0,0 +1,161 @@
+use crate::{OscErrorEvent, OscTransportError, OscTransportRegistry};
+use bevy::prelude::*;
+use rosc::decoder::MTU;
+use rosc::encoder::encode;
+use rosc::{OscBundle, OscMessage, OscPacket, OscTime};
+use std::io;
+use std::time::{Duration, SystemTime};
+
+/// Size of the `#bundle` string and the timetag that start every bundle
+const BUNDLE_HEADER_SIZE: usize = 16;
+/// Size of the length prefix of every bundle element
+const ELEMENT_HEADER_SIZE: usize = 4;
+
+const IMMEDIATELY: OscTime = OscTime {
+    seconds: 0,
+    fractional: 1,
+};
+
+/// Bevy component that collects messages to send through the transport on the same entity, e.g. an
+/// [`OscUdpClient`](crate::OscUdpClient)
+///
+/// The messages pushed during a frame are sent by the [`osc_outbox_system`] as bundles, which are
+/// split so that no packet exceeds the maximum packet size. Messages that can't be encoded or
+/// don't fit into a packet on their own are dropped and reported as [`OscErrorEvent::Send`].
+///
+/// The plugin adds an outbox to every client it opens.
+#[derive(Component, Clone, Debug)]
+pub struct OscOutbox {
+    messages: Vec<OscMessage>,
+    max_packet_size: usize,
+    latency: Option<Duration>,
+}
+
+impl Default for OscOutbox {
+    fn default() -> Self {
+        Self {
+            messages: vec![],
+            max_packet_size: MTU,
+            latency: None,
+        }
+    }
+}
+
+impl OscOutbox {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum size of a sent packet in bytes
+    ///
+    /// Defaults to [`MTU`].
+    pub fn with_max_packet_size(mut self, max_packet_size: usize) -> Self {
+        self.max_packet_size = max_packet_size;
+        self
+    }
+
+    /// Stamps bundles with a timetag `latency` after they are sent, instead of sending them to be
+    /// executed immediately
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    pub fn max_packet_size(&self) -> usize {
+        self.max_packet_size
+    }
+
+    pub fn latency(&self) -> Option<Duration> {
+        self.latency
+    }
+
+    /// Queue a message to be sent at the end of the frame
+    pub fn push(&mut self, message: OscMessage) {
+        self.messages.push(message);
+    }
+
+    /// Returns the number of messages waiting to be sent
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns true if there are no messages waiting to be sent
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Take all queued messages and pack them into bundles
+    fn flush(&mut self) -> (Vec<OscPacket>, Vec<OscTransportError>) {
+        let timetag = self
+            .latency
+            .and_then(|latency| OscTime::try_from(SystemTime::now() + latency).ok())
+            .unwrap_or(IMMEDIATELY);
+
+        let mut bundles = vec![];
+        let mut errors = vec![];
+        let mut content = vec![];
+        let mut size = BUNDLE_HEADER_SIZE;
+        for message in self.messages.drain(..) {
+            let message = OscPacket::Message(message);
+            let message_size = match encode(&message) {
+                Ok(bytes) => ELEMENT_HEADER_SIZE + bytes.len(),
+                Err(error) => {
+                    errors.push(error.into());
+                    continue;
+                }
+            };
+            if BUNDLE_HEADER_SIZE + message_size > self.max_packet_size {
+                errors.push(OscTransportError::IoError(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Message of {} bytes exceeds the maximum packet size of {} bytes",
+                        message_size, self.max_packet_size
+                    ),
+                )));
+                continue;
+            }
+            if size + message_size > self.max_packet_size {
+                bundles.push(OscPacket::Bundle(OscBundle {
+                    timetag,
+                    content: std::mem::take(&mut content),
+                }));
+                size = BUNDLE_HEADER_SIZE;
+            }
+            size += message_size;
+            content.push(message);
+        }
+        if !content.is_empty() {
+            bundles.push(OscPacket::Bundle(OscBundle { timetag, content }));
+        }
+        (bundles, errors)
+    }
+}
+
+/// Sends the messages of all [`OscOutbox`]es through the transport on the same entity
+///
+/// The plugin adds this system to [`OscSet::Send`](crate::OscSet::Send).
+pub fn osc_outbox_system(world: &mut World) {
+    let mut outgoing = vec![];
+    let mut errors = vec![];
+    let mut query = world.query::<(Entity, &mut OscOutbox)>();
+    for (entity, mut outbox) in query.iter_mut(world) {
+        if outbox.is_empty() {
+            continue;
+        }
+        let (packets, flush_errors) = outbox.flush();
+        errors.extend(flush_errors.into_iter().map(|error| OscErrorEvent::Send {
+            transport: entity,
+            error,
+        }));
+        outgoing.push((entity, packets));
+    }
+
+    for (transport, packets) in outgoing {
+        for packet in packets {
+            if let Err(error) = OscTransportRegistry::send(world, transport, &packet, None) {
+                errors.push(OscErrorEvent::Send { transport, error });
+            }
+        }
+    }
+    world.send_event_batch(errors);
+}
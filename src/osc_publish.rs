@@ -0,0 +1,330 @@
+use crate::{OscBindError, OscBindTarget, OscErrorEvent, OscOutbox, OscTransportRegistry};
+use bevy::ecs::reflect::ReflectComponent;
+use bevy::prelude::*;
+use bevy::reflect::{PartialReflect, ReflectPath, ReflectRef, TypeRegistry};
+use rosc::address::OscAddress;
+use rosc::{OscBundle, OscError, OscMessage, OscPacket, OscTime, OscType};
+use std::time::{Duration, Instant};
+
+/// When an [`OscPublish`] sends its fields
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OscPublishMode {
+    /// Send a field whenever its component changed and the field's value differs from the one
+    /// sent last
+    #[default]
+    OnChange,
+    /// Send every field at a fixed interval
+    Interval(Duration),
+    /// Send every field once after [`OscPublish::request`] was called
+    OnRequest,
+}
+
+/// A reflected field that an [`OscPublish`] sends to an address
+#[derive(Clone, Debug)]
+pub struct OscPublishField {
+    address: OscAddress,
+    target: OscBindTarget,
+    min_interval: Duration,
+    last_sent: Option<Instant>,
+    last_value: Option<Vec<OscType>>,
+    /// The value changed, but could not be sent yet because of the rate limit
+    pending: bool,
+}
+
+impl OscPublishField {
+    /// Returns a new `OscPublishField` that sends `target` to `address`
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [BadAddress](rosc::OscError::BadAddress) error when the address is invalid.
+    pub fn new(address: String, target: OscBindTarget) -> Result<Self, OscError> {
+        Ok(Self {
+            address: OscAddress::new(address)?,
+            target,
+            min_interval: Duration::ZERO,
+            last_sent: None,
+            last_value: None,
+            pending: false,
+        })
+    }
+
+    /// Sends the field at most once per `min_interval`
+    ///
+    /// Changes in between are not lost, the latest value is sent once the interval has passed.
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    fn rate_limited(&self, now: Instant) -> bool {
+        self.last_sent
+            .is_some_and(|last_sent| now.duration_since(last_sent) < self.min_interval)
+    }
+}
+
+/// Bevy component that sends reflected fields of other components on the same entity to one or
+/// more transport entities, like an [`OscUdpClient`](crate::OscUdpClient)
+///
+/// Fields are converted to OSC arguments the same way [`OscBind`](crate::OscBind) converts them
+/// back: primitives become a single argument, structs, tuples and arrays of primitives one
+/// argument per field. All fields sent in the same frame are bundled. Targets with an
+/// [`OscOutbox`] get the messages pushed to their outbox instead, which bundles them with the
+/// other messages of the frame.
+///
+/// The transports have to be registered with
+/// [`OscAppExt::add_osc_transport`](crate::OscAppExt::add_osc_transport), which the plugin does for
+/// the included ones.
+#[derive(Component)]
+pub struct OscPublish {
+    fields: Vec<OscPublishField>,
+    targets: Vec<Entity>,
+    mode: OscPublishMode,
+    requested: bool,
+}
+
+impl OscPublish {
+    /// Returns a new `OscPublish` without any fields or targets
+    pub fn new(mode: OscPublishMode) -> Self {
+        Self {
+            fields: vec![],
+            targets: vec![],
+            mode,
+            requested: false,
+        }
+    }
+
+    /// Adds a field to send
+    pub fn with_field(mut self, field: OscPublishField) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// Adds a transport entity to send to
+    pub fn with_target(mut self, target: Entity) -> Self {
+        self.targets.push(target);
+        self
+    }
+
+    pub fn mode(&self) -> OscPublishMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: OscPublishMode) {
+        self.mode = mode;
+    }
+
+    /// Send all fields the next time the [`osc_publish_system`] runs, in any mode
+    pub fn request(&mut self) {
+        self.requested = true;
+    }
+}
+
+/// What happens to a field in this run of the [`osc_publish_system`]
+struct FieldUpdate {
+    index: usize,
+    /// The value to send now
+    send: Option<Vec<OscType>>,
+    pending: bool,
+}
+
+/// Sends the fields of all [`OscPublish`] components that are due
+///
+/// The plugin adds this system to [`OscSet::Send`](crate::OscSet::Send).
+pub fn osc_publish_system(world: &mut World) {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    let last_run = world.last_change_tick();
+    let this_run = world.read_change_tick();
+    let now = Instant::now();
+
+    let mut updates = vec![];
+    let mut errors = vec![];
+    let mut query = world.query::<(Entity, &OscPublish)>();
+    for (entity, publish) in query.iter(world) {
+        let entity_ref = world.entity(entity);
+        let mut field_updates = vec![];
+        for (index, field) in publish.fields.iter().enumerate() {
+            let changed = || -> bool {
+                field
+                    .target
+                    .registration(&registry)
+                    .ok()
+                    .and_then(|registration| world.components().get_id(registration.type_id()))
+                    .and_then(|id| entity_ref.get_change_ticks_by_id(id))
+                    .is_some_and(|ticks| ticks.is_changed(last_run, this_run))
+            };
+            let due = match publish.mode {
+                OscPublishMode::OnChange => field.pending || changed(),
+                OscPublishMode::Interval(interval) => field
+                    .last_sent
+                    .is_none_or(|last_sent| now.duration_since(last_sent) >= interval),
+                OscPublishMode::OnRequest => false,
+            };
+            if !(due || publish.requested) {
+                continue;
+            }
+
+            let value = match read_field(&registry, entity_ref, &field.target) {
+                Ok(value) => value,
+                Err(error) => {
+                    errors.push(OscErrorEvent::Publish {
+                        entity,
+                        address: field.address.to_string(),
+                        error,
+                    });
+                    continue;
+                }
+            };
+            if publish.mode == OscPublishMode::OnChange
+                && !publish.requested
+                && field.last_value.as_ref() == Some(&value)
+            {
+                field_updates.push(FieldUpdate {
+                    index,
+                    send: None,
+                    pending: false,
+                });
+            } else if field.rate_limited(now) {
+                field_updates.push(FieldUpdate {
+                    index,
+                    send: None,
+                    pending: true,
+                });
+            } else {
+                field_updates.push(FieldUpdate {
+                    index,
+                    send: Some(value),
+                    pending: false,
+                });
+            }
+        }
+        if !field_updates.is_empty() || publish.requested {
+            updates.push((entity, field_updates));
+        }
+    }
+    drop(registry);
+
+    let mut outgoing = vec![];
+    for (entity, field_updates) in updates {
+        let mut publish = world.get_mut::<OscPublish>(entity).unwrap();
+        publish.requested = false;
+
+        let mut messages = vec![];
+        for update in field_updates {
+            let field = &mut publish.fields[update.index];
+            field.pending = update.pending;
+            if let Some(value) = update.send {
+                field.last_sent = Some(now);
+                messages.push(OscMessage {
+                    addr: field.address.to_string(),
+                    args: value.clone(),
+                });
+                field.last_value = Some(value);
+            }
+        }
+        if !messages.is_empty() {
+            outgoing.push((publish.targets.clone(), messages));
+        }
+    }
+
+    for (targets, messages) in outgoing {
+        let mut packet = None;
+        for transport in targets {
+            if let Some(mut outbox) = world.get_mut::<OscOutbox>(transport) {
+                for message in &messages {
+                    outbox.push(message.clone());
+                }
+                continue;
+            }
+            let packet = packet.get_or_insert_with(|| bundle(&messages));
+            if let Err(error) = OscTransportRegistry::send(world, transport, packet, None) {
+                errors.push(OscErrorEvent::Send { transport, error });
+            }
+        }
+    }
+    world.send_event_batch(errors);
+}
+
+/// Returns a single message as it is, and several as a bundle to be executed immediately
+fn bundle(messages: &[OscMessage]) -> OscPacket {
+    match messages {
+        [message] => OscPacket::Message(message.clone()),
+        _ => OscPacket::Bundle(OscBundle {
+            timetag: OscTime {
+                seconds: 0,
+                fractional: 1,
+            },
+            content: messages.iter().cloned().map(OscPacket::Message).collect(),
+        }),
+    }
+}
+
+/// Read a field and convert it to OSC arguments
+fn read_field(
+    registry: &TypeRegistry,
+    entity: EntityRef,
+    target: &OscBindTarget,
+) -> Result<Vec<OscType>, OscBindError> {
+    let reflect_component = target
+        .registration(registry)?
+        .data::<ReflectComponent>()
+        .unwrap();
+    let Some(component) = reflect_component.reflect(entity) else {
+        return Err(OscBindError::MissingComponent(
+            target.component().to_string(),
+        ));
+    };
+    let field = target
+        .path
+        .reflect_element(component.as_partial_reflect())
+        .map_err(|e| OscBindError::MissingField(e.to_string()))?;
+
+    if let Some(arg) = read_value(field) {
+        return Ok(vec![arg]);
+    }
+    let unsupported = || OscBindError::Unsupported(field.reflect_type_path().to_string());
+    let values: Vec<&dyn PartialReflect> = match field.reflect_ref() {
+        ReflectRef::Struct(s) => s.iter_fields().collect(),
+        ReflectRef::TupleStruct(s) => s.iter_fields().collect(),
+        ReflectRef::Tuple(t) => t.iter_fields().collect(),
+        ReflectRef::Array(a) => a.iter().collect(),
+        ReflectRef::List(l) => l.iter().collect(),
+        _ => return Err(unsupported()),
+    };
+    values
+        .into_iter()
+        .map(|value| read_value(value).ok_or_else(unsupported))
+        .collect()
+}
+
+/// Convert a primitive field to an OSC argument
+fn read_value(field: &dyn PartialReflect) -> Option<OscType> {
+    macro_rules! read_as {
+        ($($ty:ty => $convert:expr),* $(,)?) => {
+            $(
+                if let Some(value) = field.try_downcast_ref::<$ty>() {
+                    let convert: fn(&$ty) -> OscType = $convert;
+                    return Some(convert(value));
+                }
+            )*
+        };
+    }
+    read_as!(
+        f32 => |v| OscType::Float(*v),
+        f64 => |v| OscType::Double(*v),
+        i32 => |v| OscType::Int(*v),
+        i64 => |v| OscType::Long(*v),
+        i8 => |v| OscType::Int(*v as i32),
+        i16 => |v| OscType::Int(*v as i32),
+        u8 => |v| OscType::Int(*v as i32),
+        u16 => |v| OscType::Int(*v as i32),
+        u32 => |v| OscType::Long(*v as i64),
+        u64 => |v| OscType::Long(*v as i64),
+        isize => |v| OscType::Long(*v as i64),
+        usize => |v| OscType::Long(*v as i64),
+        bool => |v| OscType::Bool(*v),
+        char => |v| OscType::Char(*v),
+        String => |v| OscType::String(v.clone()),
+    );
+    None
+}
@@ -0,0 +1,578 @@
+use crate::osc_address_space::OscAddressSpaceRevision;
+use crate::osc_app_ext::{receive_schedule, OscRegisteredMethods};
+use crate::{osc_type_tag, OscDispatchEvent, OscErrorEvent, OscSet};
+use bevy::prelude::*;
+use rosc::encoder::encode;
+use rosc::{OscMessage, OscPacket, OscType};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::io;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tungstenite::{Message, WebSocket};
+
+/// How long server threads sleep or block before checking for work again
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How long a client may take to send its request head, and to accept a response or value
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How many connections are handled at once, further clients wait until one is closed
+const MAX_CONNECTIONS: usize = 64;
+
+/// Whether a parameter can be read and/or written by OSCQuery clients
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OscQueryAccess {
+    None = 0,
+    ReadOnly = 1,
+    WriteOnly = 2,
+    #[default]
+    ReadWrite = 3,
+}
+
+/// The range of a single argument of a parameter
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct OscQueryRange {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+/// Additional information that the OSCQuery server exposes about all addresses of the
+/// [`OscMethod`](crate::OscMethod) on the same entity
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct OscQueryInfo {
+    pub description: Option<String>,
+    /// OSC type tags of the arguments, e.g. `"ff"`. Derived from the last received value if `None`.
+    pub type_tag: Option<String>,
+    pub ranges: Vec<OscQueryRange>,
+    pub access: OscQueryAccess,
+}
+
+/// A single parameter of the namespace
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Parameter {
+    info: Option<OscQueryInfo>,
+    value: Option<Vec<OscType>>,
+}
+
+/// A WebSocket connection and the addresses it listens to
+struct Listener {
+    addresses: HashSet<String>,
+    sender: Sender<Vec<u8>>,
+}
+
+/// State shared between the app and the server threads
+#[derive(Default)]
+struct Shared {
+    parameters: BTreeMap<String, Parameter>,
+    listeners: Vec<Arc<Mutex<Listener>>>,
+}
+
+/// An OSCQuery server, inserted as a resource by the [`OscQueryPlugin`]
+///
+/// Serves the namespace of all [`OscMethod`](crate::OscMethod)s registered with
+/// [`OscAppExt::add_osc_method`](crate::OscAppExt::add_osc_method) over HTTP, and streams the
+/// values of addresses to WebSocket clients that sent a `LISTEN` command for them.
+///
+/// Dropping the server stops it and waits for its threads to finish.
+#[derive(Resource)]
+pub struct OscQueryServer {
+    local_addr: SocketAddr,
+    shared: Arc<Mutex<Shared>>,
+    running: Arc<AtomicBool>,
+    accept_thread: Option<JoinHandle<()>>,
+}
+
+impl OscQueryServer {
+    /// Start serving on `addr`
+    ///
+    /// * `host_info` - The `HOST_INFO` JSON object, `EXTENSIONS` are added to it
+    pub fn new(addr: SocketAddr, mut host_info: Map<String, Value>) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let local_addr = listener.local_addr()?;
+
+        host_info.insert(
+            "EXTENSIONS".into(),
+            json!({
+                "ACCESS": true,
+                "CONTENTS": true,
+                "DESCRIPTION": true,
+                "FULL_PATH": true,
+                "LISTEN": true,
+                "RANGE": true,
+                "TYPE": true,
+                "VALUE": true,
+            }),
+        );
+        let host_info = Value::Object(host_info);
+
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let running = Arc::new(AtomicBool::new(true));
+        let (thread_shared, thread_running) = (shared.clone(), running.clone());
+        let accept_thread = thread::spawn(move || {
+            let mut connections: Vec<JoinHandle<()>> = vec![];
+            while thread_running.load(Ordering::Relaxed) {
+                connections.retain(|connection| !connection.is_finished());
+                if connections.len() >= MAX_CONNECTIONS {
+                    thread::sleep(POLL_INTERVAL);
+                    continue;
+                }
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let (shared, running) = (thread_shared.clone(), thread_running.clone());
+                        let host_info = host_info.clone();
+                        connections.push(thread::spawn(move || {
+                            let _ = handle_connection(stream, &shared, &running, &host_info);
+                        }));
+                    }
+                    // Nothing to accept, or the connection was aborted before accepting it
+                    Err(_) => thread::sleep(POLL_INTERVAL),
+                }
+            }
+            for connection in connections {
+                let _ = connection.join();
+            }
+        });
+
+        Ok(Self {
+            local_addr,
+            shared,
+            running,
+            accept_thread: Some(accept_thread),
+        })
+    }
+
+    /// Returns the address the HTTP server is listening on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Replace the namespace, keeping the values of addresses that still exist
+    fn set_namespace(&self, namespace: BTreeMap<String, Option<OscQueryInfo>>) {
+        let mut shared = self.shared.lock().unwrap();
+        let mut parameters = BTreeMap::new();
+        for (address, info) in namespace {
+            let value = shared
+                .parameters
+                .remove(&address)
+                .and_then(|parameter| parameter.value);
+            parameters.insert(address, Parameter { info, value });
+        }
+        shared.parameters = parameters;
+    }
+
+    /// Store the value of a message and send it to all listening WebSocket clients
+    fn set_value(&self, message: &OscMessage) {
+        let mut shared = self.shared.lock().unwrap();
+        let Some(parameter) = shared.parameters.get_mut(&message.addr) else {
+            return;
+        };
+        parameter.value = Some(message.args.clone());
+
+        let mut bytes = None;
+        shared.listeners.retain(|listener| {
+            let listener = listener.lock().unwrap();
+            if !listener.addresses.contains(&message.addr) {
+                return true;
+            }
+            let bytes = bytes.get_or_insert_with(|| encode(&OscPacket::Message(message.clone())));
+            match bytes {
+                // Fails if the connection was closed
+                Ok(bytes) => listener.sender.send(bytes.clone()).is_ok(),
+                Err(_) => true,
+            }
+        });
+    }
+}
+
+impl Drop for OscQueryServer {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(accept_thread) = self.accept_thread.take() {
+            let _ = accept_thread.join();
+        }
+    }
+}
+
+/// Read the request head without consuming it, so a WebSocket handshake can read it again
+///
+/// Gives up after [`REQUEST_TIMEOUT`] or when the server stops. The stream has to have a read
+/// timeout so waiting for data can't block longer.
+fn peek_request(stream: &TcpStream, running: &AtomicBool) -> io::Result<String> {
+    let deadline = Instant::now() + REQUEST_TIMEOUT;
+    let mut buf = vec![0; 4096];
+    while running.load(Ordering::Relaxed) && Instant::now() < deadline {
+        match stream.peek(&mut buf) {
+            Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+            Ok(num_bytes) => {
+                let head = String::from_utf8_lossy(&buf[..num_bytes]);
+                if head.contains("\r\n\r\n") {
+                    return Ok(head.into_owned());
+                }
+                if num_bytes == buf.len() {
+                    return Err(ErrorKind::InvalidData.into());
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Err(ErrorKind::TimedOut.into())
+}
+
+fn handle_connection(
+    mut stream: TcpStream,
+    shared: &Mutex<Shared>,
+    running: &AtomicBool,
+    host_info: &Value,
+) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(POLL_INTERVAL))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+    let head = peek_request(&stream, running)?;
+    if head
+        .lines()
+        .any(|line| line.to_ascii_lowercase().starts_with("upgrade: websocket"))
+    {
+        let websocket = tungstenite::accept(stream).map_err(|e| io::Error::other(e.to_string()))?;
+        return handle_websocket(websocket, shared, running);
+    }
+
+    // Consume the request head
+    let mut buf = vec![0; head.find("\r\n\r\n").unwrap() + 4];
+    stream.read_exact(&mut buf)?;
+
+    let target = head
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("GET "))
+        .and_then(|line| line.split(' ').next());
+    let (status, body) = match target {
+        None => ("405 Method Not Allowed", None),
+        Some(target) => {
+            let (path, query) = target.split_once('?').unwrap_or((target, ""));
+            let response = if query == "HOST_INFO" {
+                Some(host_info.clone())
+            } else {
+                respond(&shared.lock().unwrap().parameters, path, query)
+            };
+            match response {
+                Some(body) => ("200 OK", Some(body)),
+                None => ("404 Not Found", None),
+            }
+        }
+    };
+
+    let body = body.map(|body| body.to_string()).unwrap_or_default();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// Handle `LISTEN` and `IGNORE` commands and send values until the connection is closed
+fn handle_websocket(
+    mut websocket: WebSocket<TcpStream>,
+    shared: &Mutex<Shared>,
+    running: &AtomicBool,
+) -> io::Result<()> {
+    websocket.get_mut().set_read_timeout(Some(POLL_INTERVAL))?;
+    let (sender, receiver): (_, Receiver<Vec<u8>>) = channel();
+    let listener = Arc::new(Mutex::new(Listener {
+        addresses: HashSet::new(),
+        sender,
+    }));
+    shared.lock().unwrap().listeners.push(listener.clone());
+
+    while running.load(Ordering::Relaxed) {
+        match websocket.read() {
+            Ok(Message::Text(text)) => {
+                let Ok(command) = serde_json::from_str::<Value>(text.as_str()) else {
+                    continue;
+                };
+                let address = command["DATA"].as_str().unwrap_or_default().to_string();
+                match command["COMMAND"].as_str() {
+                    Some("LISTEN") => listener.lock().unwrap().addresses.insert(address),
+                    Some("IGNORE") => listener.lock().unwrap().addresses.remove(&address),
+                    _ => false,
+                };
+            }
+            Ok(Message::Close(_)) => break,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(_) => break,
+        }
+
+        for bytes in receiver.try_iter() {
+            if websocket.send(Message::Binary(bytes.into())).is_err() {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Build the JSON response for a path and an optional attribute query
+fn respond(parameters: &BTreeMap<String, Parameter>, path: &str, query: &str) -> Option<Value> {
+    let path = path.trim_end_matches('/');
+    let prefix = format!("{}/", path);
+    let parameter = parameters.get(path);
+    if parameter.is_none()
+        && !parameters
+            .keys()
+            .any(|address| address.starts_with(&prefix))
+    {
+        return None;
+    }
+
+    let node = node_json(
+        if path.is_empty() { "/" } else { path },
+        parameter,
+        parameters,
+    );
+    if query.is_empty() {
+        return Some(node);
+    }
+    let value = node.get(query)?;
+    Some(json!({ query: value }))
+}
+
+fn node_json(
+    full_path: &str,
+    parameter: Option<&Parameter>,
+    parameters: &BTreeMap<String, Parameter>,
+) -> Value {
+    let mut node = Map::new();
+    node.insert("FULL_PATH".into(), json!(full_path));
+
+    let prefix = if full_path == "/" {
+        "/".to_string()
+    } else {
+        format!("{}/", full_path)
+    };
+    let mut contents = Map::new();
+    let mut child_names: Vec<&str> = parameters
+        .keys()
+        .filter_map(|address| address.strip_prefix(&prefix))
+        .map(|rest| rest.split_once('/').map_or(rest, |(child, _)| child))
+        .collect();
+    child_names.dedup();
+    for child in child_names {
+        let child_path = format!("{}{}", prefix, child);
+        contents.insert(
+            child.into(),
+            node_json(&child_path, parameters.get(&child_path), parameters),
+        );
+    }
+
+    match parameter {
+        Some(parameter) => {
+            let info = parameter.info.clone().unwrap_or_default();
+            node.insert("ACCESS".into(), json!(info.access as u8));
+            let type_tag = info.type_tag.or_else(|| {
+                parameter
+                    .value
+                    .as_ref()
+                    .map(|value| value.iter().map(osc_type_tag).collect())
+            });
+            if let Some(type_tag) = type_tag {
+                node.insert("TYPE".into(), json!(type_tag));
+            }
+            if let Some(value) = &parameter.value {
+                node.insert(
+                    "VALUE".into(),
+                    Value::Array(value.iter().map(osc_type_json).collect()),
+                );
+            }
+            if !info.ranges.is_empty() {
+                let ranges = info
+                    .ranges
+                    .iter()
+                    .map(|range| {
+                        let mut json = Map::new();
+                        if let Some(min) = range.min {
+                            json.insert("MIN".into(), json!(min));
+                        }
+                        if let Some(max) = range.max {
+                            json.insert("MAX".into(), json!(max));
+                        }
+                        Value::Object(json)
+                    })
+                    .collect();
+                node.insert("RANGE".into(), Value::Array(ranges));
+            }
+            if let Some(description) = info.description {
+                node.insert("DESCRIPTION".into(), json!(description));
+            }
+        }
+        None => {
+            node.insert("ACCESS".into(), json!(OscQueryAccess::None as u8));
+        }
+    }
+    if !contents.is_empty() {
+        node.insert("CONTENTS".into(), Value::Object(contents));
+    }
+    Value::Object(node)
+}
+
+fn osc_type_json(arg: &OscType) -> Value {
+    match arg {
+        OscType::Int(i) => json!(i),
+        OscType::Long(l) => json!(l),
+        OscType::Float(f) => json!(f),
+        OscType::Double(d) => json!(d),
+        OscType::String(s) => json!(s),
+        OscType::Char(c) => json!(c.to_string()),
+        OscType::Bool(b) => json!(b),
+        OscType::Blob(b) => json!(b),
+        OscType::Array(a) => Value::Array(a.content.iter().map(osc_type_json).collect()),
+        _ => Value::Null,
+    }
+}
+
+/// Keeps the namespace of the [`OscQueryServer`] up to date with the registered methods
+///
+/// The namespace is only rebuilt when methods are added or removed, or [`OscQueryInfo`]s change.
+pub fn osc_query_namespace_system(
+    world: &mut World,
+    mut last_revision: Local<Option<u64>>,
+    changed_info: &mut QueryState<(), Changed<OscQueryInfo>>,
+) {
+    let revision = world
+        .get_resource::<OscAddressSpaceRevision>()
+        .map_or(0, |revision| revision.0);
+    if *last_revision == Some(revision) && changed_info.iter(world).next().is_none() {
+        return;
+    }
+    *last_revision = Some(revision);
+
+    let Some(collectors) = world
+        .get_resource::<OscRegisteredMethods>()
+        .map(|registered| {
+            registered
+                .0
+                .values()
+                .map(|method| method.collect)
+                .collect::<Vec<_>>()
+        })
+    else {
+        return;
+    };
+
+    let mut namespace = BTreeMap::new();
+    for collect in collectors {
+        for (entity, addresses) in collect(world) {
+            let info = world.get::<OscQueryInfo>(entity).cloned();
+            for address in addresses {
+                namespace.insert(address.to_string(), info.clone());
+            }
+        }
+    }
+
+    if let Some(server) = world.get_resource::<OscQueryServer>() {
+        server.set_namespace(namespace);
+    }
+}
+
+/// Stores the values of received messages in the [`OscQueryServer`] and streams them to
+/// listening clients
+pub fn osc_query_value_system(
+    server: Option<Res<OscQueryServer>>,
+    mut events: EventReader<OscDispatchEvent>,
+) {
+    let Some(server) = server else {
+        events.clear();
+        return;
+    };
+    for event in events.read() {
+        for (_, message, _) in &event.messages {
+            server.set_value(message);
+        }
+    }
+}
+
+/// Plugin that starts an [`OscQueryServer`]
+///
+/// Add it after the [`BevyRoscPlugin`](crate::BevyRoscPlugin).
+pub struct OscQueryPlugin {
+    addr: SocketAddr,
+    name: String,
+    osc_port: Option<u16>,
+}
+
+impl OscQueryPlugin {
+    /// Returns a plugin serving OSCQuery over HTTP on `addr`
+    pub fn new(addr: impl Into<SocketAddr>) -> Self {
+        Self {
+            addr: addr.into(),
+            name: "bevy_rosc".into(),
+            osc_port: None,
+        }
+    }
+
+    /// Sets the `NAME` reported in `HOST_INFO`
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Sets the UDP port reported in `HOST_INFO` that clients send OSC messages to
+    pub fn with_osc_port(mut self, osc_port: u16) -> Self {
+        self.osc_port = Some(osc_port);
+        self
+    }
+}
+
+impl Plugin for OscQueryPlugin {
+    fn build(&self, app: &mut App) {
+        let mut host_info = Map::new();
+        host_info.insert("NAME".into(), json!(self.name));
+        host_info.insert("OSC_TRANSPORT".into(), json!("UDP"));
+        if let Some(osc_port) = self.osc_port {
+            host_info.insert("OSC_PORT".into(), json!(osc_port));
+        }
+
+        app.add_event::<OscErrorEvent>();
+        match OscQueryServer::new(self.addr, host_info) {
+            Ok(server) => {
+                app.insert_resource(server);
+            }
+            Err(error) => {
+                error!(
+                    "Failed to start OSCQuery server on {}: {}",
+                    self.addr, error
+                );
+                app.world_mut().send_event(OscErrorEvent::Bind {
+                    addr: self.addr,
+                    error,
+                });
+            }
+        }
+
+        // Removing info doesn't show up as a change, so it counts as a change of the address space
+        app.init_resource::<OscAddressSpaceRevision>().add_observer(
+            |_: Trigger<OnReplace, OscQueryInfo>, mut revision: ResMut<OscAddressSpaceRevision>| {
+                revision.0 += 1;
+            },
+        );
+        app.add_systems(
+            receive_schedule(app),
+            (osc_query_namespace_system, osc_query_value_system)
+                .chain()
+                .in_set(OscSet::Dispatch),
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::VecDeque;
+
+/// What happens when an item arrives at a full queue
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OscOverflowPolicy {
+    /// Drop the oldest queued item to make room for the new one
+    #[default]
+    DropOldest,
+    /// Drop the new item
+    DropNewest,
+}
+
+/// How many received messages an OSC method keeps until they are read
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OscQueuePolicy {
+    /// Keep every message
+    #[default]
+    Unbounded,
+    /// Keep at most `capacity` messages, the `overflow` policy decides which ones are dropped
+    Bounded {
+        capacity: usize,
+        overflow: OscOverflowPolicy,
+    },
+}
+
+impl OscQueuePolicy {
+    /// Keep at most `capacity` messages
+    pub fn bounded(capacity: usize, overflow: OscOverflowPolicy) -> Self {
+        Self::Bounded { capacity, overflow }
+    }
+
+    /// Only keep the most recent message
+    pub fn keep_latest() -> Self {
+        Self::bounded(1, OscOverflowPolicy::DropOldest)
+    }
+}
+
+/// A queue bounded according to an [`OscQueuePolicy`] that counts dropped items
+#[derive(Debug)]
+pub(crate) struct OscQueue<T> {
+    items: VecDeque<T>,
+    policy: OscQueuePolicy,
+    dropped: u64,
+}
+
+impl<T> Default for OscQueue<T> {
+    fn default() -> Self {
+        Self::new(OscQueuePolicy::default())
+    }
+}
+
+impl<T> OscQueue<T> {
+    pub(crate) fn new(policy: OscQueuePolicy) -> Self {
+        Self {
+            items: VecDeque::new(),
+            policy,
+            dropped: 0,
+        }
+    }
+
+    pub(crate) fn policy(&self) -> OscQueuePolicy {
+        self.policy
+    }
+
+    /// Change the policy, dropping the oldest items that exceed the new capacity
+    pub(crate) fn set_policy(&mut self, policy: OscQueuePolicy) {
+        self.policy = policy;
+        if let Some(capacity) = self.capacity() {
+            while self.items.len() > capacity {
+                self.items.pop_front();
+                self.dropped += 1;
+            }
+        }
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        match self.policy {
+            OscQueuePolicy::Unbounded => None,
+            OscQueuePolicy::Bounded { capacity, .. } => Some(capacity),
+        }
+    }
+
+    pub(crate) fn push(&mut self, item: T) {
+        let Some(capacity) = self.capacity() else {
+            self.items.push_back(item);
+            return;
+        };
+        if self.items.len() >= capacity {
+            self.dropped += 1;
+            let drop_newest = matches!(
+                self.policy,
+                OscQueuePolicy::Bounded {
+                    overflow: OscOverflowPolicy::DropNewest,
+                    ..
+                }
+            );
+            if drop_newest || capacity == 0 {
+                return;
+            }
+            self.items.pop_front();
+        }
+        self.items.push_back(item);
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<T> {
+        self.items.pop_front()
+    }
+
+    /// Returns the number of items dropped because the queue was full
+    pub(crate) fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
@@ -0,0 +1,93 @@
+use crate::osc_queue::OscQueue;
+use crate::{OscOverflowPolicy, OscQueuePolicy, OscReceivedPacket, OscTransportError};
+use rosc::decoder::decode_udp;
+use std::io;
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// How long a receive thread sleeps when nothing was received
+///
+/// The sockets stay non-blocking, since blocking mode is shared by all handles of a socket and the
+/// transport component has to keep sending without blocking.
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// How long a receive thread waits after a receive error
+const ERROR_INTERVAL: Duration = Duration::from_millis(100);
+
+type Received = Result<OscReceivedPacket, OscTransportError>;
+
+/// Bounded queue that a receive thread hands packets to the ECS through
+#[derive(Debug)]
+pub(crate) struct OscReceiveQueue(Mutex<OscQueue<Received>>);
+
+impl OscReceiveQueue {
+    fn push(&self, item: Received) {
+        self.0.lock().unwrap().push(item);
+    }
+
+    /// Take the oldest queued packet
+    pub(crate) fn pop(&self) -> Option<Received> {
+        self.0.lock().unwrap().pop()
+    }
+
+    /// Returns the number of packets dropped because the queue was full
+    pub(crate) fn dropped(&self) -> u64 {
+        self.0.lock().unwrap().dropped()
+    }
+}
+
+/// Spawn a thread that receives datagrams with the blocking `recv` and decodes them into a queue
+///
+/// `recv` has to be non-blocking. The thread stops once the returned queue is dropped.
+pub(crate) fn spawn_receive_thread<F>(
+    name: String,
+    buffer_size: usize,
+    capacity: usize,
+    policy: OscOverflowPolicy,
+    mut recv: F,
+) -> io::Result<Arc<OscReceiveQueue>>
+where
+    F: FnMut(&mut [u8]) -> io::Result<(usize, Option<SocketAddr>)> + Send + 'static,
+{
+    let queue = Arc::new(OscReceiveQueue(Mutex::new(OscQueue::new(
+        OscQueuePolicy::bounded(capacity.max(1), policy),
+    ))));
+    let weak = Arc::downgrade(&queue);
+
+    thread::Builder::new().name(name).spawn(move || {
+        let mut buf = vec![0; buffer_size];
+        loop {
+            let item = match recv(&mut buf) {
+                Ok((num_bytes, source)) => match decode_udp(&buf[..num_bytes]) {
+                    Ok((_, packet)) => Ok(OscReceivedPacket::new(packet, source)),
+                    Err(error) => Err(OscTransportError::DecodeError {
+                        error,
+                        bytes: buf[..num_bytes].to_vec(),
+                        source,
+                    }),
+                },
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    if weak.strong_count() == 0 {
+                        break;
+                    }
+                    thread::sleep(POLL_INTERVAL);
+                    continue;
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    // Don't spin on errors that persist
+                    thread::sleep(ERROR_INTERVAL);
+                    Err(OscTransportError::IoError(e))
+                }
+            };
+            match weak.upgrade() {
+                Some(queue) => queue.push(item),
+                None => break,
+            }
+        }
+    })?;
+    Ok(queue)
+}
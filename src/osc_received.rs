@@ -0,0 +1,104 @@
+use crate::{OscDispatchEvent, OscMessageMeta};
+use bevy::ecs::event::{EventCursor, EventId};
+use bevy::prelude::*;
+use rosc::OscMessage;
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+/// Triggered on every entity whose [`OscMethod`](crate::OscMethod) received a message
+///
+/// Observers react to messages as soon as they are dispatched, instead of polling the method in a
+/// system:
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_rosc::{OscReceived, SingleAddressOscMethod};
+/// # let mut app = App::new();
+/// app.world_mut()
+///     .spawn(SingleAddressOscMethod::new("/play".into()).unwrap())
+///     .observe(|trigger: Trigger<OscReceived>| {
+///         info!("{} received {:?}", trigger.target(), trigger.message);
+///     });
+/// ```
+///
+/// Global observers added with [`App::add_observer`] receive the events of all entities. Messages
+/// are only triggered once an observer for `OscReceived` was added after the
+/// [`BevyRoscPlugin`](crate::BevyRoscPlugin).
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct OscReceived {
+    pub message: OscMessage,
+    pub meta: OscMessageMeta,
+}
+
+/// Whether an observer for [`OscReceived`] was added, so messages aren't cloned for nobody
+///
+/// The event type only gets a component id once an observer for it is set up, or it is triggered.
+#[derive(Resource, Default)]
+pub struct OscReceivedObserved(pub(crate) bool);
+
+impl OscReceivedObserved {
+    /// Insert the flag and keep it up to date as observers are added to `app`
+    pub(crate) fn track(app: &mut App) {
+        let observed = OscReceived::component_id(app.world()).is_some();
+        app.insert_resource(Self(observed)).add_observer(
+            |_: Trigger<OnAdd, Observer>, mut commands: Commands| {
+                // The event of a new observer is registered by a command queued before this one
+                commands.queue(|world: &mut World| {
+                    let observed = OscReceived::component_id(world).is_some();
+                    world.resource_mut::<Self>().0 |= observed;
+                });
+            },
+        );
+    }
+}
+
+/// Triggered globally for every dispatched message that no [`OscMethod`](crate::OscMethod) matched
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct OscUnmatched {
+    pub message: OscMessage,
+    pub meta: OscMessageMeta,
+}
+
+/// The messages that dispatcher systems delivered to a method, by dispatch event and index
+///
+/// Lets [`osc_unmatched_system`] see what the methods accepted, including methods that override
+/// [`OscMethod::match_message_with_meta`](crate::OscMethod::match_message_with_meta).
+#[derive(Resource, Default)]
+pub struct OscMatchedMessages(Mutex<HashSet<(EventId<OscDispatchEvent>, usize)>>);
+
+impl OscMatchedMessages {
+    /// Record that the messages at `indices` of the dispatch event `id` were matched
+    pub(crate) fn insert(&self, id: EventId<OscDispatchEvent>, indices: Vec<usize>) {
+        if indices.is_empty() {
+            return;
+        }
+        let mut matched = self.0.lock().unwrap();
+        matched.extend(indices.into_iter().map(|index| (id, index)));
+    }
+}
+
+/// Triggers [`OscUnmatched`] for messages that no dispatcher system delivered to an
+/// [`OscMethod`](crate::OscMethod)
+///
+/// The plugin adds this system after [`OscSet::Dispatch`](crate::OscSet::Dispatch).
+pub fn osc_unmatched_system(world: &mut World, mut cursor: Local<EventCursor<OscDispatchEvent>>) {
+    let matched = world
+        .get_resource_mut::<OscMatchedMessages>()
+        .map(|mut matched| std::mem::take(matched.0.get_mut().unwrap()))
+        .unwrap_or_default();
+
+    let mut unmatched = vec![];
+    for (ev, id) in cursor.read_with_id(world.resource::<Events<OscDispatchEvent>>()) {
+        for (index, (_, message, meta)) in ev.messages.iter().enumerate() {
+            if !matched.contains(&(id, index)) {
+                unmatched.push(OscUnmatched {
+                    message: message.clone(),
+                    meta: meta.clone(),
+                });
+            }
+        }
+    }
+    for event in unmatched {
+        world.trigger(event);
+    }
+}
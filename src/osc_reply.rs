@@ -0,0 +1,71 @@
+use crate::{OscErrorEvent, OscMessageMeta, OscTransportError, OscTransportRegistry};
+use bevy::ecs::event::EventCursor;
+use bevy::prelude::*;
+use rosc::OscPacket;
+use std::io;
+
+/// Send this event to reply to the sender of a received message
+///
+/// The reply is sent through the transport entity that received the original message (e.g. from
+/// the socket of an [`OscUdpServer`](crate::OscUdpServer)) to the message's source address.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_rosc::{OscReplyEvent, SingleAddressOscMethod};
+/// # use rosc::{OscMessage, OscPacket};
+/// fn pong(mut query: Query<&mut SingleAddressOscMethod>, mut replies: EventWriter<OscReplyEvent>) {
+///     for mut method in &mut query {
+///         while let Some((_, meta)) = method.get_message_with_meta() {
+///             let pong = OscMessage { addr: "/pong".into(), args: vec![] };
+///             replies.write(OscReplyEvent::new(&meta, OscPacket::Message(pong)));
+///         }
+///     }
+/// }
+/// ```
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct OscReplyEvent {
+    /// Metadata of the message to reply to
+    pub meta: OscMessageMeta,
+    pub packet: OscPacket,
+}
+
+impl OscReplyEvent {
+    /// Returns a new `OscReplyEvent` replying `packet` to the sender of the message with `meta`
+    pub fn new(meta: &OscMessageMeta, packet: OscPacket) -> Self {
+        Self {
+            meta: meta.clone(),
+            packet,
+        }
+    }
+}
+
+/// Sends all [`OscReplyEvent`]s through the transport that received the original message
+///
+/// Failures are reported as [`OscErrorEvent::Reply`]. The plugin adds this system to
+/// [`OscSet::Send`](crate::OscSet::Send).
+pub fn osc_reply_system(world: &mut World, mut cursor: Local<EventCursor<OscReplyEvent>>) {
+    let replies: Vec<OscReplyEvent> = cursor
+        .read(world.resource::<Events<OscReplyEvent>>())
+        .cloned()
+        .collect();
+
+    let mut errors = vec![];
+    for reply in replies {
+        let result = match reply.meta.server {
+            Some(server) => {
+                OscTransportRegistry::send(world, server, &reply.packet, reply.meta.source)
+            }
+            None => Err(OscTransportError::IoError(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "The message was not received through a transport",
+            ))),
+        };
+        if let Err(error) = result {
+            errors.push(OscErrorEvent::Reply {
+                meta: reply.meta,
+                error,
+            });
+        }
+    }
+    world.send_event_batch(errors);
+}
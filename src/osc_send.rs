@@ -0,0 +1,148 @@
+use crate::{OscErrorEvent, OscTransport, OscTransportError, OscTransportRegistry, OscUdpClient};
+use bevy::ecs::event::EventCursor;
+use bevy::prelude::*;
+use rosc::OscPacket;
+use std::io;
+use std::net::SocketAddr;
+
+/// Where an [`OscSendEvent`] is sent to
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum OscSendTarget {
+    /// A transport entity, e.g. an [`OscUdpClient`]
+    Entity(Entity),
+    /// The entity with this [`Name`] that has a transport registered in the
+    /// [`OscTransportRegistry`]
+    Name(String),
+    /// Any address, the packet is sent over UDP from a socket owned by the plugin
+    Addr(SocketAddr),
+}
+
+impl From<Entity> for OscSendTarget {
+    fn from(entity: Entity) -> Self {
+        OscSendTarget::Entity(entity)
+    }
+}
+
+impl From<&str> for OscSendTarget {
+    fn from(name: &str) -> Self {
+        OscSendTarget::Name(name.to_string())
+    }
+}
+
+impl From<String> for OscSendTarget {
+    fn from(name: String) -> Self {
+        OscSendTarget::Name(name)
+    }
+}
+
+impl From<SocketAddr> for OscSendTarget {
+    fn from(addr: SocketAddr) -> Self {
+        OscSendTarget::Addr(addr)
+    }
+}
+
+/// Send this event to send a packet without accessing the transport components
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_rosc::OscSendEvent;
+/// # use rosc::{OscMessage, OscPacket};
+/// fn ping(mut events: EventWriter<OscSendEvent>) {
+///     let ping = OscMessage { addr: "/ping".into(), args: vec![] };
+///     events.write(OscSendEvent::new("mixer", OscPacket::Message(ping)));
+/// }
+/// ```
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct OscSendEvent {
+    pub target: OscSendTarget,
+    pub packet: OscPacket,
+}
+
+impl OscSendEvent {
+    pub fn new(target: impl Into<OscSendTarget>, packet: OscPacket) -> Self {
+        Self {
+            target: target.into(),
+            packet,
+        }
+    }
+}
+
+/// The sockets [`OscSendTarget::Addr`] packets are sent from, one per address family, opened on
+/// first use
+#[derive(Resource, Default)]
+struct OscSendSockets {
+    v4: Option<OscUdpClient>,
+    v6: Option<OscUdpClient>,
+}
+
+/// Sends all [`OscSendEvent`]s to their targets
+///
+/// Failures are reported as [`OscErrorEvent::Send`], or [`OscErrorEvent::SendTo`] if the target
+/// isn't a transport entity. The plugin adds this system to [`OscSet::Send`](crate::OscSet::Send).
+pub fn osc_send_system(world: &mut World, mut cursor: Local<EventCursor<OscSendEvent>>) {
+    let events: Vec<OscSendEvent> = cursor
+        .read(world.resource::<Events<OscSendEvent>>())
+        .cloned()
+        .collect();
+
+    let mut named = world.query::<(Entity, &Name)>();
+    let mut errors = vec![];
+    for event in events {
+        let transport = match &event.target {
+            OscSendTarget::Entity(entity) => Some(*entity),
+            OscSendTarget::Name(name) => {
+                let registry = world.get_resource::<OscTransportRegistry>();
+                named
+                    .iter(world)
+                    .find(|(entity, entity_name)| {
+                        entity_name.as_str() == name
+                            && registry.is_some_and(|r| r.has_transport(world, *entity))
+                    })
+                    .map(|(entity, _)| entity)
+            }
+            OscSendTarget::Addr(addr) => {
+                if let Err(error) = send_to_addr(world, &event.packet, *addr) {
+                    errors.push(OscErrorEvent::SendTo {
+                        target: event.target,
+                        error,
+                    });
+                }
+                continue;
+            }
+        };
+
+        match transport {
+            Some(transport) => {
+                if let Err(error) =
+                    OscTransportRegistry::send(world, transport, &event.packet, None)
+                {
+                    errors.push(OscErrorEvent::Send { transport, error });
+                }
+            }
+            None => errors.push(OscErrorEvent::SendTo {
+                target: event.target,
+                error: OscTransportError::IoError(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "No transport entity with this name",
+                )),
+            }),
+        }
+    }
+    world.send_event_batch(errors);
+}
+
+fn send_to_addr(
+    world: &mut World,
+    packet: &OscPacket,
+    addr: SocketAddr,
+) -> Result<(), OscTransportError> {
+    let mut sockets = world.get_resource_or_init::<OscSendSockets>();
+    let socket = match addr {
+        SocketAddr::V4(_) => &mut sockets.v4,
+        SocketAddr::V6(_) => &mut sockets.v6,
+    };
+    if socket.is_none() {
+        *socket = Some(OscUdpClient::new(addr)?);
+    }
+    socket.as_mut().unwrap().send_packet(packet, Some(addr))
+}
@@ -0,0 +1,36 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+
+/// The stages of OSC processing, configured by the [`BevyRoscPlugin`](crate::BevyRoscPlugin)
+///
+/// `Receive` and `Dispatch` run in that order in [`OscSchedules::receive`], `Send` runs in
+/// [`OscSchedules::send`]. Order your own systems after [`OscSet::Dispatch`] to see messages in
+/// the same frame they were received.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OscSet {
+    /// Transports receive packets and hand them to the [`OscDispatcher`](crate::OscDispatcher)
+    Receive,
+    /// Received messages are delivered to [`OscMethod`](crate::OscMethod)s
+    Dispatch,
+    /// Outgoing packets are sent, [`OscOutbox`](crate::OscOutbox)es last so they include the
+    /// messages queued by the other systems of this set
+    Send,
+}
+
+/// The schedules the [`OscSet`]s run in, inserted by the [`BevyRoscPlugin`](crate::BevyRoscPlugin)
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct OscSchedules {
+    /// Schedule of [`OscSet::Receive`] and [`OscSet::Dispatch`], [`PreUpdate`] by default
+    pub receive: InternedScheduleLabel,
+    /// Schedule of [`OscSet::Send`], [`PostUpdate`] by default
+    pub send: InternedScheduleLabel,
+}
+
+impl Default for OscSchedules {
+    fn default() -> Self {
+        Self {
+            receive: PreUpdate.intern(),
+            send: PostUpdate.intern(),
+        }
+    }
+}
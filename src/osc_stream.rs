@@ -0,0 +1,342 @@
+use crate::{OscReceivedPacket, OscTransportError};
+use rosc::decoder::decode_udp;
+use rosc::encoder::encode;
+use rosc::{OscError, OscPacket};
+use std::collections::VecDeque;
+use std::io;
+use std::io::{ErrorKind, Read, Write};
+use std::net::SocketAddr;
+
+const SLIP_END: u8 = 0xC0;
+const SLIP_ESC: u8 = 0xDB;
+const SLIP_ESC_END: u8 = 0xDC;
+const SLIP_ESC_ESC: u8 = 0xDD;
+
+/// Default maximum size of a single frame on stream-based transports, 1 MiB
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 1024 * 1024;
+
+/// Default maximum number of bytes waiting to be sent on a stream connection, 4 MiB
+pub const DEFAULT_MAX_SEND_BUFFER_SIZE: usize = 4 * 1024 * 1024;
+
+/// How OSC packets are delimited on stream-based transports like TCP
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OscStreamFraming {
+    /// OSC 1.0 framing: every packet is preceded by its size as a big-endian 32-bit integer
+    #[default]
+    LengthPrefixed,
+    /// OSC 1.1 framing: packets are delimited with double-ended [SLIP](https://www.rfc-editor.org/rfc/rfc1055)
+    Slip,
+}
+
+impl OscStreamFraming {
+    /// Encode an [`OscPacket`] and wrap it in a frame
+    pub fn encode(&self, packet: &OscPacket) -> Result<Vec<u8>, OscError> {
+        let bytes = encode(packet)?;
+
+        Ok(match self {
+            OscStreamFraming::LengthPrefixed => {
+                let mut frame = Vec::with_capacity(bytes.len() + 4);
+                frame.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+                frame.extend_from_slice(&bytes);
+                frame
+            }
+            OscStreamFraming::Slip => {
+                let mut frame = Vec::with_capacity(bytes.len() + 2);
+                frame.push(SLIP_END);
+                for byte in bytes {
+                    match byte {
+                        SLIP_END => frame.extend_from_slice(&[SLIP_ESC, SLIP_ESC_END]),
+                        SLIP_ESC => frame.extend_from_slice(&[SLIP_ESC, SLIP_ESC_ESC]),
+                        _ => frame.push(byte),
+                    }
+                }
+                frame.push(SLIP_END);
+                frame
+            }
+        })
+    }
+}
+
+/// Incrementally splits a byte stream into frames and decodes the contained [`OscPacket`]s
+///
+/// A frame larger than the maximum frame size can't be skipped reliably, so the decoder reports
+/// an error and stops decoding the stream. See [`is_overflowed`](Self::is_overflowed).
+#[derive(Debug)]
+pub struct OscStreamDecoder {
+    framing: OscStreamFraming,
+    buf: Vec<u8>,
+    max_frame_size: usize,
+    overflowed: bool,
+}
+
+impl Default for OscStreamDecoder {
+    fn default() -> Self {
+        Self::new(OscStreamFraming::default())
+    }
+}
+
+impl OscStreamDecoder {
+    pub fn new(framing: OscStreamFraming) -> Self {
+        Self {
+            framing,
+            buf: vec![],
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            overflowed: false,
+        }
+    }
+
+    /// Set the maximum size of a frame, [`DEFAULT_MAX_FRAME_SIZE`] by default
+    ///
+    /// For length-prefixed framing this is the size of the packet, for SLIP the size of the
+    /// escaped packet.
+    pub fn with_max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Returns the maximum size of a frame
+    pub fn max_frame_size(&self) -> usize {
+        self.max_frame_size
+    }
+
+    /// Returns true once a frame exceeded the maximum size
+    ///
+    /// The rest of the stream is ignored after that, its connection should be closed.
+    pub fn is_overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    /// Append bytes read from the stream
+    pub fn push(&mut self, bytes: &[u8]) {
+        if !self.overflowed {
+            self.buf.extend_from_slice(bytes);
+        }
+    }
+
+    /// Decode the next complete packet, returns `Ok(None)` if more bytes are needed
+    ///
+    /// A frame that fails to decode is discarded, so decoding can continue with the next frame.
+    /// The only exception is a frame exceeding the maximum size, after which nothing is decoded
+    /// anymore.
+    pub fn next_packet(&mut self) -> Result<Option<OscPacket>, OscError> {
+        match self.next_frame() {
+            Ok(Some(frame)) => decode_udp(&frame).map(|(_, packet)| Some(packet)),
+            Ok(None) => Ok(None),
+            Err((e, _)) => Err(e),
+        }
+    }
+
+    /// Split off the next complete frame without decoding it
+    ///
+    /// On error, the raw bytes of the broken frame are returned alongside the error.
+    pub(crate) fn next_frame(&mut self) -> Result<Option<Vec<u8>>, (OscError, Vec<u8>)> {
+        match self.framing {
+            OscStreamFraming::LengthPrefixed => self.next_length_prefixed_frame(),
+            OscStreamFraming::Slip => loop {
+                match self.next_slip_frame()? {
+                    // Double-ended SLIP produces empty frames between packets
+                    Some(frame) if frame.is_empty() => continue,
+                    frame => break Ok(frame),
+                }
+            },
+        }
+    }
+
+    /// Give up on the stream, returning the buffered bytes with the error
+    fn overflow(&mut self) -> (OscError, Vec<u8>) {
+        self.overflowed = true;
+        (
+            OscError::BadPacket("Frame exceeds the maximum frame size"),
+            std::mem::take(&mut self.buf),
+        )
+    }
+
+    fn next_length_prefixed_frame(&mut self) -> Result<Option<Vec<u8>>, (OscError, Vec<u8>)> {
+        if self.buf.len() < 4 {
+            return Ok(None);
+        }
+        let size =
+            u32::from_be_bytes([self.buf[0], self.buf[1], self.buf[2], self.buf[3]]) as usize;
+        if size > self.max_frame_size {
+            return Err(self.overflow());
+        }
+        if self.buf.len() < 4 + size {
+            return Ok(None);
+        }
+
+        let frame = self.buf[4..4 + size].to_vec();
+        self.buf.drain(..4 + size);
+        Ok(Some(frame))
+    }
+
+    fn next_slip_frame(&mut self) -> Result<Option<Vec<u8>>, (OscError, Vec<u8>)> {
+        let end = match self
+            .buf
+            .iter()
+            .take(self.max_frame_size + 1)
+            .position(|b| *b == SLIP_END)
+        {
+            Some(end) => end,
+            None if self.buf.len() > self.max_frame_size => return Err(self.overflow()),
+            None => return Ok(None),
+        };
+
+        let raw: Vec<u8> = self.buf.drain(..=end).take(end).collect();
+        let mut frame = Vec::with_capacity(raw.len());
+        let mut bytes = raw.iter().copied();
+        while let Some(byte) = bytes.next() {
+            if byte != SLIP_ESC {
+                frame.push(byte);
+                continue;
+            }
+            match bytes.next() {
+                Some(SLIP_ESC_END) => frame.push(SLIP_END),
+                Some(SLIP_ESC_ESC) => frame.push(SLIP_ESC),
+                _ => {
+                    return Err((
+                        OscError::BadPacket("Invalid SLIP escape sequence"),
+                        raw.clone(),
+                    ))
+                }
+            }
+        }
+
+        Ok(Some(frame))
+    }
+}
+
+/// A single non-blocking stream that sends and receives framed OSC packets
+pub(crate) struct OscStreamConnection<S: Read + Write> {
+    stream: S,
+    peer: Option<SocketAddr>,
+    framing: OscStreamFraming,
+    decoder: OscStreamDecoder,
+    /// Framed bytes that could not be written yet because the stream would have blocked
+    outgoing: Vec<u8>,
+    max_send_buffer_size: usize,
+    closed: bool,
+}
+
+impl<S: Read + Write> OscStreamConnection<S> {
+    pub(crate) fn new(
+        stream: S,
+        peer: Option<SocketAddr>,
+        framing: OscStreamFraming,
+        max_frame_size: usize,
+        max_send_buffer_size: usize,
+    ) -> Self {
+        Self {
+            stream,
+            peer,
+            framing,
+            decoder: OscStreamDecoder::new(framing).with_max_frame_size(max_frame_size),
+            outgoing: vec![],
+            max_send_buffer_size,
+            closed: false,
+        }
+    }
+
+    pub(crate) fn stream(&self) -> &S {
+        &self.stream
+    }
+
+    /// Returns the address of the remote end of the stream, if it has one
+    pub(crate) fn peer(&self) -> Option<SocketAddr> {
+        self.peer
+    }
+
+    /// Returns true once the peer closed the stream or it failed
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Read everything that is available without blocking and decode all complete packets
+    ///
+    /// Packets are decoded after every read, so at most one incomplete frame is buffered and the
+    /// connection is closed as soon as a frame exceeds the maximum size.
+    pub(crate) fn read_available(
+        &mut self,
+        received: &mut VecDeque<Result<OscReceivedPacket, OscTransportError>>,
+    ) {
+        let mut buf = [0; 4096];
+        while !self.closed {
+            match self.stream.read(&mut buf) {
+                Ok(0) => self.closed = true,
+                Ok(num_bytes) => {
+                    self.decoder.push(&buf[..num_bytes]);
+                    self.decode(received);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.closed = true;
+                    received.push_back(Err(OscTransportError::IoError(e)));
+                }
+            }
+        }
+    }
+
+    /// Decode all complete packets in the decoder's buffer
+    fn decode(&mut self, received: &mut VecDeque<Result<OscReceivedPacket, OscTransportError>>) {
+        loop {
+            let (result, bytes) = match self.decoder.next_frame() {
+                Ok(Some(frame)) => (decode_udp(&frame).map(|(_, packet)| packet), frame),
+                Ok(None) => break,
+                Err((error, bytes)) => (Err(error), bytes),
+            };
+            received.push_back(match result {
+                Ok(packet) => Ok(OscReceivedPacket::new(packet, self.peer)),
+                Err(error) => Err(OscTransportError::DecodeError {
+                    error,
+                    bytes,
+                    source: self.peer,
+                }),
+            });
+        }
+        if self.decoder.is_overflowed() {
+            self.closed = true;
+        }
+    }
+
+    /// Frame a packet and write it to the stream
+    ///
+    /// Bytes that can't be written without blocking are kept and written by later calls to
+    /// [`send`](Self::send) or [`flush`](Self::flush). If more than the maximum send buffer size
+    /// is left, the peer isn't reading and the connection is closed.
+    pub(crate) fn send(&mut self, packet: &OscPacket) -> Result<(), OscTransportError> {
+        let frame = self.framing.encode(packet)?;
+        self.outgoing.extend_from_slice(&frame);
+        self.flush()?;
+        if self.outgoing.len() > self.max_send_buffer_size {
+            self.closed = true;
+            self.outgoing = vec![];
+            return Err(OscTransportError::IoError(io::Error::new(
+                ErrorKind::ConnectionAborted,
+                "Send buffer is full, the peer stopped reading",
+            )));
+        }
+        Ok(())
+    }
+
+    /// Write as many pending outgoing bytes as possible without blocking
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        while !self.outgoing.is_empty() {
+            match self.stream.write(&self.outgoing) {
+                Ok(0) => {
+                    self.closed = true;
+                    return Err(ErrorKind::WriteZero.into());
+                }
+                Ok(num_bytes) => {
+                    self.outgoing.drain(..num_bytes);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.closed = true;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
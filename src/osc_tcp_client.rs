@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+use std::io;
+use std::net::{SocketAddr, TcpStream};
+
+use rosc::OscPacket;
+
+use crate::osc_stream::OscStreamConnection;
+use crate::{
+    OscReceivedPacket, OscStreamFraming, OscTransport, OscTransportError, DEFAULT_MAX_FRAME_SIZE,
+    DEFAULT_MAX_SEND_BUFFER_SIZE,
+};
+
+/// Bevy component that sends OSC packets to a TCP server
+///
+/// Packets sent back by the server over the same connection can be read with
+/// [`recv`](Self::recv).
+#[derive(Component)]
+pub struct OscTcpClient {
+    connection: OscStreamConnection<TcpStream>,
+    addr: SocketAddr,
+    received: VecDeque<Result<OscReceivedPacket, OscTransportError>>,
+}
+
+impl OscTcpClient {
+    pub fn new(addr: SocketAddr, framing: OscStreamFraming) -> Result<Self, io::Error> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nonblocking(true)?;
+        stream.set_nodelay(true)?;
+
+        Ok(Self {
+            connection: OscStreamConnection::new(
+                stream,
+                Some(addr),
+                framing,
+                DEFAULT_MAX_FRAME_SIZE,
+                DEFAULT_MAX_SEND_BUFFER_SIZE,
+            ),
+            addr,
+            received: Default::default(),
+        })
+    }
+
+    /// Returns the address of the server this client is connected to
+    pub fn peer_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the local address of the connection
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.connection.stream().local_addr()
+    }
+
+    /// Returns true once the server closed the connection
+    pub fn is_closed(&self) -> bool {
+        self.connection.is_closed()
+    }
+
+    /// Send a packet to the server
+    ///
+    /// If the packet can't be written without blocking, the remainder is written on the next call
+    /// to `send`, [`flush`](Self::flush) or [`recv`](Self::recv).
+    pub fn send(&mut self, packet: &OscPacket) -> Result<(), OscTransportError> {
+        self.connection.send(packet)
+    }
+
+    /// Write any outgoing data that is still pending
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.connection.flush()
+    }
+
+    /// Receive the next packet sent by the server, or `Ok(None)` if there is nothing to receive
+    pub fn recv(&mut self) -> Result<Option<OscPacket>, OscTransportError> {
+        Ok(self.recv_packet()?.map(|received| received.packet))
+    }
+}
+
+impl OscTransport for OscTcpClient {
+    fn recv_packet(&mut self) -> Result<Option<OscReceivedPacket>, OscTransportError> {
+        if self.received.is_empty() {
+            self.connection.flush()?;
+            self.connection.read_available(&mut self.received);
+        }
+
+        self.received.pop_front().transpose()
+    }
+
+    /// The connection only has a single peer, so `target` is ignored
+    fn send_packet(
+        &mut self,
+        packet: &OscPacket,
+        _target: Option<SocketAddr>,
+    ) -> Result<(), OscTransportError> {
+        self.send(packet)
+    }
+}
@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+use std::io;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+
+use rosc::OscPacket;
+
+use crate::osc_stream::OscStreamConnection;
+use crate::{
+    OscReceivedPacket, OscStreamFraming, OscTransport, OscTransportError, DEFAULT_MAX_FRAME_SIZE,
+    DEFAULT_MAX_SEND_BUFFER_SIZE,
+};
+
+/// Bevy component that accepts OSC connections over TCP
+///
+/// Any number of peers can connect at the same time. Packets are delimited with the
+/// [`OscStreamFraming`] chosen when creating the server.
+#[derive(Component)]
+pub struct OscTcpServer {
+    listener: TcpListener,
+    framing: OscStreamFraming,
+    max_frame_size: usize,
+    max_send_buffer_size: usize,
+    connections: Vec<OscStreamConnection<TcpStream>>,
+    /// Packets and errors that were read from the connections but not yet returned by `recv`
+    received: VecDeque<Result<OscReceivedPacket, OscTransportError>>,
+}
+
+impl OscTcpServer {
+    pub fn new<A: ToSocketAddrs>(addr: A, framing: OscStreamFraming) -> Result<Self, io::Error> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+
+        Ok(Self {
+            listener,
+            framing,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            max_send_buffer_size: DEFAULT_MAX_SEND_BUFFER_SIZE,
+            connections: vec![],
+            received: Default::default(),
+        })
+    }
+
+    /// Returns the local address the server is listening on
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Returns the framing used by all connections of this server
+    pub fn framing(&self) -> OscStreamFraming {
+        self.framing
+    }
+
+    /// Set the maximum size of a frame, [`DEFAULT_MAX_FRAME_SIZE`] by default
+    ///
+    /// A connection that sends a larger frame is reported as a
+    /// [`DecodeError`](OscTransportError::DecodeError) and closed.
+    pub fn with_max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Returns the maximum size of a frame
+    pub fn max_frame_size(&self) -> usize {
+        self.max_frame_size
+    }
+
+    /// Set how many bytes may wait to be sent on a connection, [`DEFAULT_MAX_SEND_BUFFER_SIZE`] by
+    /// default
+    ///
+    /// A connection whose peer stops reading is closed once this is exceeded.
+    pub fn with_max_send_buffer_size(mut self, max_send_buffer_size: usize) -> Self {
+        self.max_send_buffer_size = max_send_buffer_size;
+        self
+    }
+
+    /// Returns how many bytes may wait to be sent on a connection
+    pub fn max_send_buffer_size(&self) -> usize {
+        self.max_send_buffer_size
+    }
+
+    /// Returns the number of currently open connections
+    pub fn connection_count(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Receive the next packet from any connection, or `Ok(None)` if there is nothing to receive
+    pub fn recv(&mut self) -> Result<Option<OscPacket>, OscTransportError> {
+        Ok(self.recv_from()?.map(|received| received.packet))
+    }
+
+    /// Same as [`recv`](Self::recv), but also returns the address of the sending peer
+    pub fn recv_from(&mut self) -> Result<Option<OscReceivedPacket>, OscTransportError> {
+        if self.received.is_empty() {
+            self.poll();
+        }
+
+        self.received.pop_front().transpose()
+    }
+
+    /// Send a packet to the connected peer with address `addr`
+    pub fn send_to(
+        &mut self,
+        packet: &OscPacket,
+        addr: SocketAddr,
+    ) -> Result<(), OscTransportError> {
+        match self
+            .connections
+            .iter_mut()
+            .find(|connection| connection.peer() == Some(addr))
+        {
+            Some(connection) => connection.send(packet),
+            None => Err(OscTransportError::IoError(io::Error::new(
+                ErrorKind::NotConnected,
+                format!("No connection to {}", addr),
+            ))),
+        }
+    }
+
+    /// Send a packet to all connected peers
+    pub fn broadcast(&mut self, packet: &OscPacket) -> Result<(), OscTransportError> {
+        let mut result = Ok(());
+        for connection in &mut self.connections {
+            if let Err(e) = connection.send(packet) {
+                result = Err(e);
+            }
+        }
+        result
+    }
+
+    /// Accept new connections and read everything that is available from the existing ones
+    ///
+    /// Accept errors like running out of file descriptors are queued after the packets of the
+    /// existing connections, so those keep being read.
+    fn poll(&mut self) {
+        let mut accept_error = None;
+        loop {
+            match self.listener.accept() {
+                Ok((stream, addr)) => match stream.set_nonblocking(true) {
+                    Ok(()) => self.connections.push(OscStreamConnection::new(
+                        stream,
+                        Some(addr),
+                        self.framing,
+                        self.max_frame_size,
+                        self.max_send_buffer_size,
+                    )),
+                    Err(err) => accept_error = Some(err),
+                },
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) => {
+                    accept_error = Some(err);
+                    break;
+                }
+            }
+        }
+
+        for connection in &mut self.connections {
+            // Connections may still have outgoing data pending from earlier sends
+            let _ = connection.flush();
+            connection.read_available(&mut self.received);
+        }
+        self.connections
+            .retain(|connection| !connection.is_closed());
+
+        if let Some(err) = accept_error {
+            self.received
+                .push_back(Err(OscTransportError::IoError(err)));
+        }
+    }
+}
+
+impl OscTransport for OscTcpServer {
+    fn recv_packet(&mut self) -> Result<Option<OscReceivedPacket>, OscTransportError> {
+        self.recv_from()
+    }
+
+    /// Sends to the connection of `target`, or to all connections if no target is given
+    fn send_packet(
+        &mut self,
+        packet: &OscPacket,
+        target: Option<SocketAddr>,
+    ) -> Result<(), OscTransportError> {
+        match target {
+            Some(addr) => self.send_to(packet, addr),
+            None => self.broadcast(packet),
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{OscDispatchEvent, OscDispatcher, OscErrorEvent, OscMessageMeta, OscTransportError};
+use bevy::ecs::component::Mutable;
+use bevy::prelude::*;
+use rosc::OscPacket;
+use std::any::TypeId;
+use std::io;
+use std::net::SocketAddr;
+use std::time::Instant;
+
+/// An [`OscPacket`] received by an [`OscTransport`] together with information about its sender
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscReceivedPacket {
+    pub packet: OscPacket,
+    /// Address of the sender, or `None` if the transport has no notion of network addresses
+    pub source: Option<SocketAddr>,
+    /// When the packet was received
+    pub received_at: Instant,
+}
+
+impl OscReceivedPacket {
+    /// Returns a new `OscReceivedPacket` that was received just now
+    pub fn new(packet: OscPacket, source: Option<SocketAddr>) -> Self {
+        Self {
+            packet,
+            source,
+            received_at: Instant::now(),
+        }
+    }
+}
+
+/// A non-blocking source and/or sink of OSC packets
+///
+/// Implement this for your component to have its packets delivered to the [`OscDispatcher`] by
+/// [`osc_receive_system`].
+pub trait OscTransport {
+    /// Receive the next pending packet without blocking
+    ///
+    /// Returns `Ok(None)` if there is nothing left to receive at the moment.
+    fn recv_packet(&mut self) -> Result<Option<OscReceivedPacket>, OscTransportError>;
+
+    /// Send a packet without blocking
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The peer to send the packet to. If `None`, the packet is sent to the
+    ///   transport's default peer(s), e.g. the server a client was created for.
+    fn send_packet(
+        &mut self,
+        packet: &OscPacket,
+        target: Option<SocketAddr>,
+    ) -> Result<(), OscTransportError>;
+}
+
+/// Receives all pending packets from every transport component of type `T` and forwards them to
+/// the [`OscDispatcher`]
+///
+/// The plugin adds this system for all transports included in bevy_rosc. Add it yourself for
+/// custom transports.
+///
+/// Receive and dispatch failures are reported as [`OscErrorEvent`]s. If a transport reports an IO
+/// error, the remaining packets of that transport are received in the next frame.
+pub fn osc_receive_system<T: OscTransport + Component<Mutability = Mutable>>(
+    mut osc_dispatcher: ResMut<OscDispatcher>,
+    mut query: Query<(Entity, &mut T)>,
+    event_writer: EventWriter<OscDispatchEvent>,
+    mut error_writer: EventWriter<OscErrorEvent>,
+) {
+    let mut osc_packets = vec![];
+    for (entity, mut transport) in query.iter_mut() {
+        loop {
+            match transport.recv_packet() {
+                Ok(Some(received)) => osc_packets.push((
+                    received.packet,
+                    OscMessageMeta {
+                        source: received.source,
+                        server: Some(entity),
+                        received_at: received.received_at,
+                        timetag: None,
+                        late: false,
+                    },
+                )),
+                Ok(None) => break,
+                Err(error) => {
+                    // IO errors tend to persist, so don't keep polling the transport this frame
+                    let io_error = matches!(error, OscTransportError::IoError(_));
+                    error_writer.write(OscErrorEvent::Receive {
+                        server: entity,
+                        error,
+                    });
+                    if io_error {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    error_writer.write_batch(osc_dispatcher.dispatch_received(osc_packets, event_writer));
+}
+
+/// Sends a packet through the transport component of an entity, returns `None` if the entity
+/// doesn't have that component
+type OscSendFn =
+    fn(&mut World, Entity, &OscPacket, Option<SocketAddr>) -> Option<Result<(), OscTransportError>>;
+
+/// Returns true if an entity has the transport component
+type OscHasFn = fn(&World, Entity) -> bool;
+
+/// All transport component types registered with
+/// [`OscAppExt::add_osc_transport`](crate::OscAppExt::add_osc_transport)
+///
+/// Allows sending packets through a transport entity without knowing the type of its transport.
+#[derive(Resource, Default, Clone)]
+pub struct OscTransportRegistry {
+    senders: Vec<(TypeId, OscHasFn, OscSendFn)>,
+}
+
+impl OscTransportRegistry {
+    /// Register the transport component type `T`, returns false if it already was
+    pub fn register<T: OscTransport + Component<Mutability = Mutable>>(&mut self) -> bool {
+        if self.contains::<T>() {
+            return false;
+        }
+        self.senders.push((
+            TypeId::of::<T>(),
+            |world, entity| world.get::<T>(entity).is_some(),
+            |world, entity, packet, target| {
+                let mut transport = world.get_mut::<T>(entity)?;
+                Some(transport.send_packet(packet, target))
+            },
+        ));
+        true
+    }
+
+    /// Returns true if `T` is registered
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.senders
+            .iter()
+            .any(|(type_id, _, _)| *type_id == TypeId::of::<T>())
+    }
+
+    /// Returns true if `entity` has a registered transport component
+    pub fn has_transport(&self, world: &World, entity: Entity) -> bool {
+        self.senders.iter().any(|(_, has, _)| has(world, entity))
+    }
+
+    /// Send a packet through the transport of `entity`, see [`OscTransport::send_packet`]
+    ///
+    /// Fails with [`ErrorKind::NotFound`](io::ErrorKind::NotFound) if the entity has no
+    /// registered transport.
+    pub fn send(
+        world: &mut World,
+        entity: Entity,
+        packet: &OscPacket,
+        target: Option<SocketAddr>,
+    ) -> Result<(), OscTransportError> {
+        let registry = world.get_resource::<Self>().cloned().unwrap_or_default();
+        for (_, _, send) in &registry.senders {
+            if let Some(result) = send(world, entity, packet, target) {
+                return result;
+            }
+        }
+        Err(OscTransportError::IoError(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Entity {} has no registered OSC transport", entity),
+        )))
+    }
+}
@@ -1,30 +1,101 @@
 use bevy::prelude::*;
 use std::io;
+use std::io::ErrorKind;
 use std::net::{SocketAddr, UdpSocket};
 
+use rosc::decoder::{decode_udp, MTU};
 use rosc::encoder::encode;
 use rosc::OscPacket;
 
+use crate::osc_udp_options::{canonical_addr, target_addr, unspecified_for};
+use crate::{OscReceivedPacket, OscTransport, OscTransportError, OscUdpOptions};
+
 #[derive(Component)]
 pub struct OscUdpClient {
     socket: UdpSocket,
     addr: SocketAddr,
+    /// Reused for every datagram sent back to the client
+    buf: Vec<u8>,
 }
 
 impl OscUdpClient {
+    /// Returns a client sending to `addr` from a free port on all local addresses of the same
+    /// family
     pub fn new(addr: SocketAddr) -> Result<Self, io::Error> {
-        let socket = UdpSocket::bind("0.0.0.0:0")?;
-        socket.set_nonblocking(true)?;
+        Self::with_options(addr, &OscUdpOptions::default())
+    }
 
-        Ok(Self { socket, addr })
+    /// Same as [`new`](Self::new), but opens the socket with `options`, e.g. to send to a
+    /// broadcast address
+    pub fn with_options(addr: SocketAddr, options: &OscUdpOptions) -> Result<Self, io::Error> {
+        Self::bind(unspecified_for(addr), addr, options)
     }
 
-    pub fn send(&self, packet: &OscPacket) -> io::Result<()> {
-        let buf = encode(packet).unwrap();
+    /// Returns a client sending to `addr` from the explicit local address `local_addr`
+    pub fn bind(
+        local_addr: SocketAddr,
+        addr: SocketAddr,
+        options: &OscUdpOptions,
+    ) -> Result<Self, io::Error> {
+        Ok(Self::from_socket(options.bind(local_addr)?, addr))
+    }
 
-        match self.socket.send_to(&buf, self.addr) {
-            Err(e) => Err(e),
-            Ok(_) => Ok(()),
+    fn from_socket(socket: UdpSocket, addr: SocketAddr) -> Self {
+        Self {
+            socket,
+            addr,
+            buf: vec![0; MTU],
         }
     }
+
+    /// Sets the size of the buffer for packets sent back to the client
+    ///
+    /// Defaults to [`MTU`]. Larger datagrams are truncated and fail to decode.
+    pub fn with_buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buf = vec![0; buffer_size];
+        self
+    }
+
+    /// Send a packet to the client's target address
+    ///
+    /// Use an [`OscOutbox`](crate::OscOutbox) to bundle the messages sent during a frame.
+    pub fn send(&self, packet: &OscPacket) -> Result<(), OscTransportError> {
+        self.send_to(packet, self.addr)
+    }
+
+    fn send_to(&self, packet: &OscPacket, addr: SocketAddr) -> Result<(), OscTransportError> {
+        let buf = encode(packet)?;
+        self.socket.send_to(&buf, target_addr(&self.socket, addr))?;
+        Ok(())
+    }
+}
+
+impl OscTransport for OscUdpClient {
+    /// Receives packets sent back to the client's socket, e.g. replies of the server
+    fn recv_packet(&mut self) -> Result<Option<OscReceivedPacket>, OscTransportError> {
+        let buf = &mut self.buf;
+        match self.socket.recv_from(buf) {
+            Ok((num_bytes, addr)) => match decode_udp(&buf[0..num_bytes]) {
+                Ok((_, packet)) => Ok(Some(OscReceivedPacket::new(
+                    packet,
+                    Some(canonical_addr(addr)),
+                ))),
+                Err(error) => Err(OscTransportError::DecodeError {
+                    error,
+                    bytes: buf[0..num_bytes].to_vec(),
+                    source: Some(canonical_addr(addr)),
+                }),
+            },
+            Err(err) if err.kind() == ErrorKind::WouldBlock => Ok(None),
+            Err(err) => Err(OscTransportError::IoError(err)),
+        }
+    }
+
+    fn send_packet(
+        &mut self,
+        packet: &OscPacket,
+        target: Option<SocketAddr>,
+    ) -> Result<(), OscTransportError> {
+        self.send_to(packet, target.unwrap_or(self.addr))
+    }
 }
@@ -0,0 +1,170 @@
+use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+
+/// Socket options for [`OscUdpServer`](crate::OscUdpServer)s and
+/// [`OscUdpClient`](crate::OscUdpClient)s, e.g. to receive from a multicast group
+///
+/// Options that don't apply to the address family of the socket are ignored, except multicast
+/// groups, which fail to join.
+///
+/// IPv6 sockets are dual-stack by default: a server listening on `[::]` also receives from IPv4
+/// peers. Their addresses are reported as IPv4 addresses, not as IPv4-mapped IPv6 addresses.
+///
+/// ```no_run
+/// # use bevy_rosc::{OscUdpOptions, OscUdpServer};
+/// # use std::net::Ipv4Addr;
+/// let options = OscUdpOptions::new()
+///     .with_reuse_address(true)
+///     .join_multicast_v4(Ipv4Addr::new(239, 0, 0, 1), Ipv4Addr::UNSPECIFIED);
+/// let server = OscUdpServer::with_options("0.0.0.0:31337", &options).unwrap();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OscUdpOptions {
+    reuse_address: bool,
+    only_v6: bool,
+    broadcast: bool,
+    multicast_v4: Vec<(Ipv4Addr, Ipv4Addr)>,
+    multicast_v6: Vec<(Ipv6Addr, u32)>,
+    multicast_interface_v4: Option<Ipv4Addr>,
+    multicast_interface_v6: Option<u32>,
+    multicast_ttl: Option<u32>,
+    multicast_loop: Option<bool>,
+}
+
+impl OscUdpOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows several sockets, also of other processes, to bind the same port
+    ///
+    /// Sets `SO_REUSEADDR`, and `SO_REUSEPORT` on Unix. Every socket sharing the port has to set
+    /// this.
+    pub fn with_reuse_address(mut self, reuse_address: bool) -> Self {
+        self.reuse_address = reuse_address;
+        self
+    }
+
+    /// Restricts IPv6 sockets to IPv6 peers (`IPV6_V6ONLY`)
+    pub fn with_only_v6(mut self, only_v6: bool) -> Self {
+        self.only_v6 = only_v6;
+        self
+    }
+
+    /// Allows sending to broadcast addresses (`SO_BROADCAST`)
+    pub fn with_broadcast(mut self, broadcast: bool) -> Self {
+        self.broadcast = broadcast;
+        self
+    }
+
+    /// Joins an IPv4 multicast group on the interface with the address `interface`
+    ///
+    /// Use [`Ipv4Addr::UNSPECIFIED`] to let the system choose the interface.
+    pub fn join_multicast_v4(mut self, group: Ipv4Addr, interface: Ipv4Addr) -> Self {
+        self.multicast_v4.push((group, interface));
+        self
+    }
+
+    /// Joins an IPv6 multicast group on the interface with the index `interface`
+    ///
+    /// Use `0` to let the system choose the interface.
+    pub fn join_multicast_v6(mut self, group: Ipv6Addr, interface: u32) -> Self {
+        self.multicast_v6.push((group, interface));
+        self
+    }
+
+    /// Sends IPv4 multicast packets from the interface with the address `interface`
+    pub fn with_multicast_interface_v4(mut self, interface: Ipv4Addr) -> Self {
+        self.multicast_interface_v4 = Some(interface);
+        self
+    }
+
+    /// Sends IPv6 multicast packets from the interface with the index `interface`
+    pub fn with_multicast_interface_v6(mut self, interface: u32) -> Self {
+        self.multicast_interface_v6 = Some(interface);
+        self
+    }
+
+    /// Sets the time to live, or hop limit for IPv6, of sent multicast packets
+    pub fn with_multicast_ttl(mut self, ttl: u32) -> Self {
+        self.multicast_ttl = Some(ttl);
+        self
+    }
+
+    /// Sets whether sent multicast packets are looped back to the local host
+    pub fn with_multicast_loop(mut self, multicast_loop: bool) -> Self {
+        self.multicast_loop = Some(multicast_loop);
+        self
+    }
+
+    /// Opens a nonblocking UDP socket bound to `addr` with these options
+    pub fn bind(&self, addr: SocketAddr) -> io::Result<UdpSocket> {
+        let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP))?;
+        if self.reuse_address {
+            socket.set_reuse_address(true)?;
+            #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+            socket.set_reuse_port(true)?;
+        }
+        if addr.is_ipv6() {
+            socket.set_only_v6(self.only_v6)?;
+        }
+        socket.bind(&SockAddr::from(addr))?;
+        socket.set_nonblocking(true)?;
+        if self.broadcast {
+            socket.set_broadcast(true)?;
+        }
+
+        for (group, interface) in &self.multicast_v4 {
+            socket.join_multicast_v4(group, interface)?;
+        }
+        for (group, interface) in &self.multicast_v6 {
+            socket.join_multicast_v6(group, *interface)?;
+        }
+        if addr.is_ipv4() {
+            if let Some(interface) = &self.multicast_interface_v4 {
+                socket.set_multicast_if_v4(interface)?;
+            }
+            if let Some(ttl) = self.multicast_ttl {
+                socket.set_multicast_ttl_v4(ttl)?;
+            }
+            if let Some(multicast_loop) = self.multicast_loop {
+                socket.set_multicast_loop_v4(multicast_loop)?;
+            }
+        } else {
+            if let Some(interface) = self.multicast_interface_v6 {
+                socket.set_multicast_if_v6(interface)?;
+            }
+            if let Some(hops) = self.multicast_ttl {
+                socket.set_multicast_hops_v6(hops)?;
+            }
+            if let Some(multicast_loop) = self.multicast_loop {
+                socket.set_multicast_loop_v6(multicast_loop)?;
+            }
+        }
+        Ok(socket.into())
+    }
+}
+
+/// The unspecified address of the same family as `addr`, to bind a socket sending to `addr`
+pub(crate) fn unspecified_for(addr: SocketAddr) -> SocketAddr {
+    match addr {
+        SocketAddr::V4(_) => SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+        SocketAddr::V6(_) => SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0)),
+    }
+}
+
+/// Convert an IPv4-mapped address received on a dual-stack socket to an IPv4 address
+pub(crate) fn canonical_addr(addr: SocketAddr) -> SocketAddr {
+    SocketAddr::new(addr.ip().to_canonical(), addr.port())
+}
+
+/// Map an IPv4 target to an IPv6 address if it is sent to from an IPv6 socket
+pub(crate) fn target_addr(socket: &UdpSocket, addr: SocketAddr) -> SocketAddr {
+    match (socket.local_addr(), addr.ip()) {
+        (Ok(SocketAddr::V6(_)), IpAddr::V4(ip)) => {
+            SocketAddr::new(ip.to_ipv6_mapped().into(), addr.port())
+        }
+        _ => addr,
+    }
+}
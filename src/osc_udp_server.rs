@@ -1,42 +1,176 @@
 use bevy::prelude::*;
 use std::io;
 use std::io::ErrorKind;
-use std::net::{ToSocketAddrs, UdpSocket};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::{Arc, Mutex};
 
 use rosc::decoder::{decode_udp, MTU};
-use rosc::{OscError, OscPacket};
+use rosc::encoder::encode;
+use rosc::OscPacket;
+
+use crate::osc_receive_thread::{spawn_receive_thread, OscReceiveQueue};
+use crate::osc_udp_options::{canonical_addr, target_addr};
+use crate::{OscOverflowPolicy, OscReceivedPacket, OscTransport, OscTransportError, OscUdpOptions};
+
+/// Errors that can occur when receiving with an [`OscUdpServer`]
+#[deprecated(note = "Use `OscTransportError` instead")]
+pub type OscUdpReceiveError = OscTransportError;
 
 #[derive(Component)]
 pub struct OscUdpServer {
     socket: UdpSocket,
-}
-
-#[derive(Debug)]
-pub enum OscUdpReceiveError {
-    OscError(OscError),
-    IoError(io::Error),
+    /// Reused for every received datagram
+    buf: Mutex<Vec<u8>>,
+    /// Packets received by the receive thread, if the server has one
+    queue: Option<Arc<OscReceiveQueue>>,
 }
 
 impl OscUdpServer {
+    /// Returns a server listening on `addr`
+    ///
+    /// If `addr` resolves to several addresses, they are tried in order until one can be bound.
+    /// Listening on `[::]` receives from both IPv6 and IPv4 peers, see [`OscUdpOptions`].
     pub fn new<A: ToSocketAddrs>(addr: A) -> Result<Self, io::Error> {
-        let socket = UdpSocket::bind(addr)?;
-        socket.set_nonblocking(true)?;
+        Self::with_options(addr, &OscUdpOptions::default())
+    }
+
+    /// Same as [`new`](Self::new), but opens the socket with `options`, e.g. to join a multicast
+    /// group
+    pub fn with_options<A: ToSocketAddrs>(addr: A, options: &OscUdpOptions) -> io::Result<Self> {
+        let mut last_error = None;
+        for addr in addr.to_socket_addrs()? {
+            match options.bind(addr) {
+                Ok(socket) => return Ok(Self::from_socket(socket)),
+                Err(error) => last_error = Some(error),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            io::Error::new(
+                ErrorKind::InvalidInput,
+                "could not resolve to any addresses",
+            )
+        }))
+    }
 
-        Ok(Self { socket })
+    fn from_socket(socket: UdpSocket) -> Self {
+        Self {
+            socket,
+            buf: Mutex::new(vec![0; MTU]),
+            queue: None,
+        }
+    }
+
+    /// Sets the size of the receive buffer, which limits the size of received packets
+    ///
+    /// Defaults to [`MTU`]. Larger datagrams are truncated and fail to decode.
+    pub fn with_buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buf = Mutex::new(vec![0; buffer_size]);
+        self
+    }
+
+    /// Receives packets on a dedicated thread instead of when the server is polled
+    ///
+    /// Packets are timestamped on arrival and held in a queue of `capacity` packets until they are
+    /// received from the server, so they aren't lost when a frame takes long. If the queue is full,
+    /// packets are dropped according to `policy` and counted in
+    /// [`dropped_packets`](Self::dropped_packets). The thread stops when the server is dropped.
+    ///
+    /// Call this after [`with_buffer_size`](Self::with_buffer_size).
+    ///
+    /// Only datagram servers have receive threads. Stream servers like
+    /// [`OscTcpServer`](crate::OscTcpServer) don't lose packets when polled late, since TCP makes
+    /// the peer wait until the server reads.
+    pub fn with_receive_thread(
+        mut self,
+        capacity: usize,
+        policy: OscOverflowPolicy,
+    ) -> io::Result<Self> {
+        let socket = self.socket.try_clone()?;
+        let name = format!("osc-udp-server-{}", self.local_addr()?);
+        self.queue = Some(spawn_receive_thread(
+            name,
+            self.buf.get_mut().unwrap().len(),
+            capacity,
+            policy,
+            move |buf| {
+                let (num_bytes, addr) = socket.recv_from(buf)?;
+                Ok((num_bytes, Some(canonical_addr(addr))))
+            },
+        )?);
+        Ok(self)
     }
 
-    pub fn recv(&self) -> Result<Option<OscPacket>, OscUdpReceiveError> {
-        let mut buf = [0; MTU];
+    /// Returns the number of packets the receive thread dropped because its queue was full
+    pub fn dropped_packets(&self) -> u64 {
+        self.queue.as_ref().map_or(0, |queue| queue.dropped())
+    }
 
-        let result = self.socket.recv(&mut buf);
+    /// Returns the local address the server is bound to
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    pub fn recv(&self) -> Result<Option<OscPacket>, OscTransportError> {
+        Ok(self.recv_from()?.map(|(osc_packet, _)| osc_packet))
+    }
+
+    /// Same as [`recv`](Self::recv), but also returns the address of the sender
+    pub fn recv_from(&self) -> Result<Option<(OscPacket, SocketAddr)>, OscTransportError> {
+        // UDP packets always have a source
+        Ok(self
+            .recv_received()?
+            .map(|received| (received.packet, received.source.unwrap())))
+    }
+
+    fn recv_received(&self) -> Result<Option<OscReceivedPacket>, OscTransportError> {
+        if let Some(queue) = &self.queue {
+            return queue.pop().transpose();
+        }
+
+        let mut buf = self.buf.lock().unwrap();
+        let result = self.socket.recv_from(&mut buf);
         match result {
-            Ok(num_bytes) => match decode_udp(&buf[0..num_bytes]) {
-                Ok((_, osc_packet)) => Ok(Some(osc_packet)),
-                Err(e) => Err(OscUdpReceiveError::OscError(e)),
+            Ok((num_bytes, addr)) => match decode_udp(&buf[0..num_bytes]) {
+                Ok((_, osc_packet)) => Ok(Some(OscReceivedPacket::new(
+                    osc_packet,
+                    Some(canonical_addr(addr)),
+                ))),
+                Err(error) => Err(OscTransportError::DecodeError {
+                    error,
+                    bytes: buf[0..num_bytes].to_vec(),
+                    source: Some(canonical_addr(addr)),
+                }),
             },
 
             Err(err) if err.kind() == ErrorKind::WouldBlock => Ok(None),
-            Err(err) => Err(OscUdpReceiveError::IoError(err)),
+            Err(err) => Err(OscTransportError::IoError(err)),
+        }
+    }
+
+    /// Send a packet from the server's socket to `addr`
+    pub fn send_to(&self, packet: &OscPacket, addr: SocketAddr) -> Result<(), OscTransportError> {
+        let buf = encode(packet)?;
+        self.socket.send_to(&buf, target_addr(&self.socket, addr))?;
+        Ok(())
+    }
+}
+
+impl OscTransport for OscUdpServer {
+    fn recv_packet(&mut self) -> Result<Option<OscReceivedPacket>, OscTransportError> {
+        self.recv_received()
+    }
+
+    fn send_packet(
+        &mut self,
+        packet: &OscPacket,
+        target: Option<SocketAddr>,
+    ) -> Result<(), OscTransportError> {
+        match target {
+            Some(addr) => self.send_to(packet, addr),
+            None => Err(OscTransportError::IoError(io::Error::new(
+                ErrorKind::InvalidInput,
+                "UDP servers can only send to an explicit target",
+            ))),
         }
     }
 }
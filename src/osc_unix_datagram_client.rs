@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use std::io;
+use std::net::SocketAddr;
+use std::os::unix::net::UnixDatagram;
+use std::path::Path;
+
+use rosc::encoder::encode;
+use rosc::OscPacket;
+
+use crate::{OscReceivedPacket, OscTransport, OscTransportError};
+
+/// Bevy component that sends OSC packets to a Unix datagram socket, e.g. an
+/// [`OscUnixDatagramServer`](crate::OscUnixDatagramServer)
+#[derive(Component)]
+pub struct OscUnixDatagramClient {
+    socket: UnixDatagram,
+}
+
+impl OscUnixDatagramClient {
+    /// Returns a client sending to the socket file at `path`
+    pub fn connect<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(path)?;
+        Self::from_socket(socket)
+    }
+
+    /// Returns a client sending to the socket named `name` in the abstract namespace
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn connect_abstract<N: AsRef<[u8]>>(name: N) -> Result<Self, io::Error> {
+        use std::os::linux::net::SocketAddrExt;
+
+        let socket = UnixDatagram::unbound()?;
+        socket.connect_addr(&std::os::unix::net::SocketAddr::from_abstract_name(name)?)?;
+        Self::from_socket(socket)
+    }
+
+    fn from_socket(socket: UnixDatagram) -> Result<Self, io::Error> {
+        socket.set_nonblocking(true)?;
+        Ok(Self { socket })
+    }
+
+    pub fn send(&self, packet: &OscPacket) -> Result<(), OscTransportError> {
+        let buf = encode(packet)?;
+        self.socket.send(&buf)?;
+        Ok(())
+    }
+}
+
+impl OscTransport for OscUnixDatagramClient {
+    /// The client's socket is unbound, so nothing can be sent back to it
+    fn recv_packet(&mut self) -> Result<Option<OscReceivedPacket>, OscTransportError> {
+        Ok(None)
+    }
+
+    /// The socket is connected to a single peer, so `target` is ignored
+    fn send_packet(
+        &mut self,
+        packet: &OscPacket,
+        _target: Option<SocketAddr>,
+    ) -> Result<(), OscTransportError> {
+        self.send(packet)
+    }
+}
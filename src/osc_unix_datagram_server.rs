@@ -0,0 +1,149 @@
+use bevy::prelude::*;
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::os::unix::net::UnixDatagram;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rosc::decoder::{decode_udp, MTU};
+use rosc::OscPacket;
+
+use crate::osc_receive_thread::{spawn_receive_thread, OscReceiveQueue};
+use crate::{OscOverflowPolicy, OscReceivedPacket, OscTransport, OscTransportError};
+
+/// Bevy component that receives OSC packets on a Unix datagram socket
+///
+/// Unix socket addresses can't be represented as [`SocketAddr`], so received packets have no
+/// source and can't be replied to. The socket file is removed when the server is dropped.
+#[derive(Component)]
+pub struct OscUnixDatagramServer {
+    socket: UnixDatagram,
+    /// The socket file to remove on drop, `None` for abstract sockets
+    path: Option<PathBuf>,
+    buffer_size: usize,
+    /// Packets received by the receive thread, if the server has one
+    queue: Option<Arc<OscReceiveQueue>>,
+}
+
+impl OscUnixDatagramServer {
+    /// Returns a server bound to the socket file at `path`
+    pub fn bind<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
+        let socket = UnixDatagram::bind(&path)?;
+        Self::from_socket(socket, Some(path.as_ref().to_path_buf()))
+    }
+
+    /// Returns a server bound to `name` in the abstract namespace, which doesn't need a socket
+    /// file
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn bind_abstract<N: AsRef<[u8]>>(name: N) -> Result<Self, io::Error> {
+        use std::os::linux::net::SocketAddrExt;
+
+        let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+        Self::from_socket(UnixDatagram::bind_addr(&addr)?, None)
+    }
+
+    fn from_socket(socket: UnixDatagram, path: Option<PathBuf>) -> Result<Self, io::Error> {
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            path,
+            buffer_size: MTU,
+            queue: None,
+        })
+    }
+
+    /// Sets the size of the receive buffer, which limits the size of received packets
+    ///
+    /// Defaults to [`MTU`]. Larger datagrams are truncated and fail to decode.
+    pub fn with_buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+
+    /// Receives packets on a dedicated thread instead of when the server is polled, see
+    /// [`OscUdpServer::with_receive_thread`](crate::OscUdpServer::with_receive_thread)
+    ///
+    /// Call this after [`with_buffer_size`](Self::with_buffer_size).
+    pub fn with_receive_thread(
+        mut self,
+        capacity: usize,
+        policy: OscOverflowPolicy,
+    ) -> io::Result<Self> {
+        let socket = self.socket.try_clone()?;
+        let name = match &self.path {
+            Some(path) => format!("osc-unix-datagram-server-{}", path.display()),
+            None => "osc-unix-datagram-server".to_string(),
+        };
+        self.queue = Some(spawn_receive_thread(
+            name,
+            self.buffer_size,
+            capacity,
+            policy,
+            move |buf| Ok((socket.recv(buf)?, None)),
+        )?);
+        Ok(self)
+    }
+
+    /// Returns the number of packets the receive thread dropped because its queue was full
+    pub fn dropped_packets(&self) -> u64 {
+        self.queue.as_ref().map_or(0, |queue| queue.dropped())
+    }
+
+    /// Returns the path of the socket file, or `None` for abstract sockets
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn recv(&self) -> Result<Option<OscPacket>, OscTransportError> {
+        if let Some(queue) = &self.queue {
+            return Ok(queue.pop().transpose()?.map(|received| received.packet));
+        }
+
+        let mut buf = vec![0; self.buffer_size];
+
+        match self.socket.recv(&mut buf) {
+            Ok(num_bytes) => match decode_udp(&buf[0..num_bytes]) {
+                Ok((_, osc_packet)) => Ok(Some(osc_packet)),
+                Err(error) => Err(OscTransportError::DecodeError {
+                    error,
+                    bytes: buf[0..num_bytes].to_vec(),
+                    source: None,
+                }),
+            },
+            Err(err) if err.kind() == ErrorKind::WouldBlock => Ok(None),
+            Err(err) => Err(OscTransportError::IoError(err)),
+        }
+    }
+}
+
+impl Drop for OscUnixDatagramServer {
+    fn drop(&mut self) {
+        if let Some(path) = &self.path {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+impl OscTransport for OscUnixDatagramServer {
+    fn recv_packet(&mut self) -> Result<Option<OscReceivedPacket>, OscTransportError> {
+        if let Some(queue) = &self.queue {
+            return queue.pop().transpose();
+        }
+        Ok(self
+            .recv()?
+            .map(|packet| OscReceivedPacket::new(packet, None)))
+    }
+
+    fn send_packet(
+        &mut self,
+        _packet: &OscPacket,
+        _target: Option<SocketAddr>,
+    ) -> Result<(), OscTransportError> {
+        Err(OscTransportError::IoError(io::Error::new(
+            ErrorKind::Unsupported,
+            "Unix datagram servers can't send, use an OscUnixDatagramClient",
+        )))
+    }
+}
@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+use std::io;
+use std::net::SocketAddr;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+
+use rosc::OscPacket;
+
+use crate::osc_stream::OscStreamConnection;
+use crate::{
+    OscReceivedPacket, OscStreamFraming, OscTransport, OscTransportError, DEFAULT_MAX_FRAME_SIZE,
+    DEFAULT_MAX_SEND_BUFFER_SIZE,
+};
+
+/// Bevy component that sends OSC packets to a Unix stream socket, e.g. an
+/// [`OscUnixStreamServer`](crate::OscUnixStreamServer)
+///
+/// Packets sent back by the server over the same connection can be read with
+/// [`recv`](Self::recv).
+#[derive(Component)]
+pub struct OscUnixStreamClient {
+    connection: OscStreamConnection<UnixStream>,
+    received: VecDeque<Result<OscReceivedPacket, OscTransportError>>,
+}
+
+impl OscUnixStreamClient {
+    /// Returns a client connected to the socket file at `path`
+    pub fn connect<P: AsRef<Path>>(path: P, framing: OscStreamFraming) -> Result<Self, io::Error> {
+        Self::from_stream(UnixStream::connect(path)?, framing)
+    }
+
+    /// Returns a client connected to the socket named `name` in the abstract namespace
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn connect_abstract<N: AsRef<[u8]>>(
+        name: N,
+        framing: OscStreamFraming,
+    ) -> Result<Self, io::Error> {
+        use std::os::linux::net::SocketAddrExt;
+
+        let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+        Self::from_stream(UnixStream::connect_addr(&addr)?, framing)
+    }
+
+    fn from_stream(stream: UnixStream, framing: OscStreamFraming) -> Result<Self, io::Error> {
+        stream.set_nonblocking(true)?;
+        Ok(Self {
+            connection: OscStreamConnection::new(
+                stream,
+                None,
+                framing,
+                DEFAULT_MAX_FRAME_SIZE,
+                DEFAULT_MAX_SEND_BUFFER_SIZE,
+            ),
+            received: Default::default(),
+        })
+    }
+
+    /// Returns true once the server closed the connection
+    pub fn is_closed(&self) -> bool {
+        self.connection.is_closed()
+    }
+
+    /// Send a packet to the server
+    ///
+    /// If the packet can't be written without blocking, the remainder is written on the next call
+    /// to `send`, [`flush`](Self::flush) or [`recv`](Self::recv).
+    pub fn send(&mut self, packet: &OscPacket) -> Result<(), OscTransportError> {
+        self.connection.send(packet)
+    }
+
+    /// Write any outgoing data that is still pending
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.connection.flush()
+    }
+
+    /// Receive the next packet sent by the server, or `Ok(None)` if there is nothing to receive
+    pub fn recv(&mut self) -> Result<Option<OscPacket>, OscTransportError> {
+        Ok(self.recv_packet()?.map(|received| received.packet))
+    }
+}
+
+impl OscTransport for OscUnixStreamClient {
+    fn recv_packet(&mut self) -> Result<Option<OscReceivedPacket>, OscTransportError> {
+        if self.received.is_empty() {
+            self.connection.flush()?;
+            self.connection.read_available(&mut self.received);
+        }
+
+        self.received.pop_front().transpose()
+    }
+
+    /// The connection only has a single peer, so `target` is ignored
+    fn send_packet(
+        &mut self,
+        packet: &OscPacket,
+        _target: Option<SocketAddr>,
+    ) -> Result<(), OscTransportError> {
+        self.send(packet)
+    }
+}
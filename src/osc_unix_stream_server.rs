@@ -0,0 +1,207 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+
+use rosc::OscPacket;
+
+use crate::osc_stream::OscStreamConnection;
+use crate::{
+    OscReceivedPacket, OscStreamFraming, OscTransport, OscTransportError, DEFAULT_MAX_FRAME_SIZE,
+    DEFAULT_MAX_SEND_BUFFER_SIZE,
+};
+
+/// Bevy component that accepts OSC connections on a Unix stream socket
+///
+/// Works like an [`OscTcpServer`](crate::OscTcpServer), except that peers have no
+/// [`SocketAddr`], so packets can only be sent to all connections at once. The socket file is
+/// removed when the server is dropped.
+#[derive(Component)]
+pub struct OscUnixStreamServer {
+    listener: UnixListener,
+    /// The socket file to remove on drop, `None` for abstract sockets
+    path: Option<PathBuf>,
+    framing: OscStreamFraming,
+    max_frame_size: usize,
+    max_send_buffer_size: usize,
+    connections: Vec<OscStreamConnection<UnixStream>>,
+    /// Packets and errors that were read from the connections but not yet returned by `recv`
+    received: VecDeque<Result<OscReceivedPacket, OscTransportError>>,
+}
+
+impl OscUnixStreamServer {
+    /// Returns a server listening on the socket file at `path`
+    pub fn bind<P: AsRef<Path>>(path: P, framing: OscStreamFraming) -> Result<Self, io::Error> {
+        let listener = UnixListener::bind(&path)?;
+        Self::from_listener(listener, Some(path.as_ref().to_path_buf()), framing)
+    }
+
+    /// Returns a server listening on `name` in the abstract namespace, which doesn't need a socket
+    /// file
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn bind_abstract<N: AsRef<[u8]>>(
+        name: N,
+        framing: OscStreamFraming,
+    ) -> Result<Self, io::Error> {
+        use std::os::linux::net::SocketAddrExt;
+
+        let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+        Self::from_listener(UnixListener::bind_addr(&addr)?, None, framing)
+    }
+
+    fn from_listener(
+        listener: UnixListener,
+        path: Option<PathBuf>,
+        framing: OscStreamFraming,
+    ) -> Result<Self, io::Error> {
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            path,
+            framing,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            max_send_buffer_size: DEFAULT_MAX_SEND_BUFFER_SIZE,
+            connections: vec![],
+            received: Default::default(),
+        })
+    }
+
+    /// Returns the path of the socket file, or `None` for abstract sockets
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Set the maximum size of a frame, [`DEFAULT_MAX_FRAME_SIZE`] by default
+    ///
+    /// A connection that sends a larger frame is reported as a
+    /// [`DecodeError`](OscTransportError::DecodeError) and closed.
+    pub fn with_max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Returns the maximum size of a frame
+    pub fn max_frame_size(&self) -> usize {
+        self.max_frame_size
+    }
+
+    /// Set how many bytes may wait to be sent on a connection, [`DEFAULT_MAX_SEND_BUFFER_SIZE`] by
+    /// default
+    ///
+    /// A connection whose peer stops reading is closed once this is exceeded.
+    pub fn with_max_send_buffer_size(mut self, max_send_buffer_size: usize) -> Self {
+        self.max_send_buffer_size = max_send_buffer_size;
+        self
+    }
+
+    /// Returns how many bytes may wait to be sent on a connection
+    pub fn max_send_buffer_size(&self) -> usize {
+        self.max_send_buffer_size
+    }
+
+    /// Returns the number of currently open connections
+    pub fn connection_count(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Receive the next packet from any connection, or `Ok(None)` if there is nothing to receive
+    pub fn recv(&mut self) -> Result<Option<OscPacket>, OscTransportError> {
+        if self.received.is_empty() {
+            self.poll();
+        }
+
+        Ok(self
+            .received
+            .pop_front()
+            .transpose()?
+            .map(|received| received.packet))
+    }
+
+    /// Send a packet to all connected peers
+    pub fn broadcast(&mut self, packet: &OscPacket) -> Result<(), OscTransportError> {
+        let mut result = Ok(());
+        for connection in &mut self.connections {
+            if let Err(e) = connection.send(packet) {
+                result = Err(e);
+            }
+        }
+        result
+    }
+
+    /// Accept new connections and read everything that is available from the existing ones
+    ///
+    /// Accept errors like running out of file descriptors are queued after the packets of the
+    /// existing connections, so those keep being read.
+    fn poll(&mut self) {
+        let mut accept_error = None;
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => match stream.set_nonblocking(true) {
+                    Ok(()) => self.connections.push(OscStreamConnection::new(
+                        stream,
+                        None,
+                        self.framing,
+                        self.max_frame_size,
+                        self.max_send_buffer_size,
+                    )),
+                    Err(err) => accept_error = Some(err),
+                },
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) => {
+                    accept_error = Some(err);
+                    break;
+                }
+            }
+        }
+
+        for connection in &mut self.connections {
+            // Connections may still have outgoing data pending from earlier sends
+            let _ = connection.flush();
+            connection.read_available(&mut self.received);
+        }
+        self.connections
+            .retain(|connection| !connection.is_closed());
+
+        if let Some(err) = accept_error {
+            self.received
+                .push_back(Err(OscTransportError::IoError(err)));
+        }
+    }
+}
+
+impl Drop for OscUnixStreamServer {
+    fn drop(&mut self) {
+        if let Some(path) = &self.path {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+impl OscTransport for OscUnixStreamServer {
+    fn recv_packet(&mut self) -> Result<Option<OscReceivedPacket>, OscTransportError> {
+        if self.received.is_empty() {
+            self.poll();
+        }
+
+        self.received.pop_front().transpose()
+    }
+
+    /// Sends to all connections, a `target` is not supported
+    fn send_packet(
+        &mut self,
+        packet: &OscPacket,
+        target: Option<SocketAddr>,
+    ) -> Result<(), OscTransportError> {
+        match target {
+            Some(_) => Err(OscTransportError::IoError(io::Error::new(
+                ErrorKind::InvalidInput,
+                "Unix stream servers can only send to all connections",
+            ))),
+            None => self.broadcast(packet),
+        }
+    }
+}
@@ -1,57 +1,351 @@
 use crate::osc_dispatcher::{method_dispatcher_system, OscDispatchEvent};
 use crate::osc_method::SingleAddressOscMethod;
-use crate::{MultiAddressOscMethod, OscDispatcher, OscUdpServer};
+use crate::osc_received::{OscMatchedMessages, OscReceivedObserved};
+use crate::{
+    osc_bind_system, osc_handler_system, osc_outbox_system, osc_publish_system, osc_reply_system,
+    osc_send_system, osc_unmatched_system, MultiAddressOscMethod, OscAppExt, OscBind,
+    OscDispatcher, OscErrorEvent, OscHandler, OscLatestValue, OscLoopback, OscOutbox,
+    OscOverflowPolicy, OscReplyEvent, OscSchedules, OscSendEvent, OscSet, OscTcpClient,
+    OscTcpServer, OscUdpClient, OscUdpOptions, OscUdpServer, DEFAULT_MATCHER_CACHE_CAPACITY,
+};
+#[cfg(unix)]
+use crate::{
+    OscUnixDatagramClient, OscUnixDatagramServer, OscUnixStreamClient, OscUnixStreamServer,
+};
+use bevy::ecs::schedule::ScheduleLabel;
 use bevy::prelude::*;
+use rosc::decoder::MTU;
 use std::io;
-use std::net::ToSocketAddrs;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::time::{Duration, Instant};
 
-/// Plugin implementing the default functionality for bevy_rosc
-///
-/// It opens a single UDP server, and adds dispatching systems for both single and multi address
-/// osc methods.
-pub struct BevyRoscPlugin<A: ToSocketAddrs + Sync + 'static + Clone> {
-    addrs: A,
-}
-
-impl<A: ToSocketAddrs + Send + Sync + 'static + Clone> BevyRoscPlugin<A> {
-    pub fn new(addrs: A) -> Result<Self, io::Error> {
-        Ok(BevyRoscPlugin { addrs })
-    }
-}
-
-fn osc_receive_system(
-    mut osc_dispatcher: ResMut<OscDispatcher>,
-    mut query: Query<&mut OscUdpServer>,
-    event_writer: EventWriter<OscDispatchEvent>,
-) {
-    let mut osc_packets = vec![];
-    for osc_udp_server in query.iter_mut() {
-        loop {
-            if let Ok(o) = osc_udp_server.recv() {
-                match o {
-                    Some(p) => osc_packets.push(p),
-                    None => break,
+/// What the [`BevyRoscPlugin`] does if a listener or client socket can't be opened
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OscBindFailurePolicy {
+    /// Panic while the plugin is built
+    Panic,
+    /// Log the failure, send an [`OscErrorEvent::Bind`] and continue without the socket
+    #[default]
+    Log,
+    /// Same as `Log`, but try to open the socket again every `interval` until it succeeds
+    Retry { interval: Duration },
+}
+
+/// How the plugin opens its sockets
+#[derive(Clone, Debug)]
+struct OscSocketConfig {
+    buffer_size: usize,
+    options: OscUdpOptions,
+    /// Queue capacity and overflow policy of the listeners' receive threads
+    receive_thread: Option<(usize, OscOverflowPolicy)>,
+}
+
+/// A socket the plugin opens on startup
+#[derive(Clone, Copy, Debug)]
+enum OscSocket {
+    Listener(SocketAddr),
+    Client(SocketAddr),
+}
+
+impl OscSocket {
+    fn addr(&self) -> SocketAddr {
+        match self {
+            OscSocket::Listener(addr) | OscSocket::Client(addr) => *addr,
+        }
+    }
+
+    /// Open the socket and spawn its transport component
+    fn spawn(&self, world: &mut World, config: &OscSocketConfig) -> io::Result<Entity> {
+        Ok(match self {
+            OscSocket::Listener(addr) => {
+                let mut server = OscUdpServer::with_options(addr, &config.options)?
+                    .with_buffer_size(config.buffer_size);
+                if let Some((capacity, policy)) = config.receive_thread {
+                    server = server.with_receive_thread(capacity, policy)?;
                 }
+                world.spawn(server).id()
+            }
+            OscSocket::Client(addr) => world
+                .spawn((
+                    OscUdpClient::with_options(*addr, &config.options)?
+                        .with_buffer_size(config.buffer_size),
+                    OscOutbox::default(),
+                ))
+                .id(),
+        })
+    }
+
+    /// Report a failure to open the socket according to `policy`
+    fn report(&self, world: &mut World, policy: OscBindFailurePolicy, error: io::Error) {
+        match (self, policy) {
+            (OscSocket::Listener(addr), OscBindFailurePolicy::Panic) => {
+                panic!("Failed to open OSC listener on {}: {}", addr, error)
             }
+            (OscSocket::Client(addr), OscBindFailurePolicy::Panic) => {
+                panic!("Failed to open OSC client for {}: {}", addr, error)
+            }
+            (OscSocket::Listener(addr), _) => {
+                error!("Failed to open OSC listener on {}: {}", addr, error)
+            }
+            (OscSocket::Client(addr), _) => {
+                error!("Failed to open OSC client for {}: {}", addr, error)
+            }
+        }
+        world.send_event(OscErrorEvent::Bind {
+            addr: self.addr(),
+            error,
+        });
+    }
+}
+
+/// Sockets that failed to open and are retried by [`retry_sockets_system`]
+#[derive(Resource)]
+struct OscPendingSockets {
+    sockets: Vec<OscSocket>,
+    config: OscSocketConfig,
+    interval: Duration,
+    last_attempt: Instant,
+}
+
+fn retry_sockets_system(world: &mut World) {
+    let Some(mut pending) = world.remove_resource::<OscPendingSockets>() else {
+        return;
+    };
+    if pending.last_attempt.elapsed() < pending.interval {
+        world.insert_resource(pending);
+        return;
+    }
+
+    let policy = OscBindFailurePolicy::Retry {
+        interval: pending.interval,
+    };
+    let mut failed = vec![];
+    for socket in pending.sockets.drain(..) {
+        if let Err(error) = socket.spawn(world, &pending.config) {
+            socket.report(world, policy, error);
+            failed.push(socket);
+        }
+    }
+
+    if !failed.is_empty() {
+        pending.sockets = failed;
+        pending.last_attempt = Instant::now();
+        world.insert_resource(pending);
+    }
+}
+
+/// Plugin implementing the default functionality for bevy_rosc
+///
+/// It opens UDP servers and clients, receives packets from all included transports and adds
+/// dispatching systems for both single and multi address osc methods.
+///
+/// Sockets that can't be opened are handled according to the [`OscBindFailurePolicy`], which by
+/// default logs the failure and sends an [`OscErrorEvent::Bind`].
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_rosc::{BevyRoscPlugin, OscBindFailurePolicy};
+/// # use std::time::Duration;
+/// App::new().add_plugins(
+///     BevyRoscPlugin::default()
+///         .with_listener(([0, 0, 0, 0], 31337))
+///         .with_listener(([0, 0, 0, 0], 31338))
+///         .with_client(([127, 0, 0, 1], 9000))
+///         .with_bind_failure_policy(OscBindFailurePolicy::Retry {
+///             interval: Duration::from_secs(1),
+///         }),
+/// );
+/// ```
+pub struct BevyRoscPlugin {
+    listeners: Vec<SocketAddr>,
+    clients: Vec<SocketAddr>,
+    socket_config: OscSocketConfig,
+    schedules: OscSchedules,
+    bind_failure_policy: OscBindFailurePolicy,
+    matcher_cache_capacity: usize,
+}
+
+impl Default for BevyRoscPlugin {
+    /// A plugin without any sockets, receiving in [`PreUpdate`] and sending in [`PostUpdate`]
+    fn default() -> Self {
+        Self {
+            listeners: vec![],
+            clients: vec![],
+            socket_config: OscSocketConfig {
+                buffer_size: MTU,
+                options: OscUdpOptions::default(),
+                receive_thread: None,
+            },
+            schedules: OscSchedules::default(),
+            bind_failure_policy: OscBindFailurePolicy::default(),
+            matcher_cache_capacity: DEFAULT_MATCHER_CACHE_CAPACITY,
         }
     }
+}
+
+impl BevyRoscPlugin {
+    /// Returns a plugin with a UDP server listening on `addrs`
+    ///
+    /// If `addrs` resolves to several addresses, the first one is used. Fails if it can't be
+    /// resolved.
+    pub fn new<A: ToSocketAddrs>(addrs: A) -> Result<Self, io::Error> {
+        let addr = addrs.to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "could not resolve to any addresses",
+            )
+        })?;
+        Ok(Self::default().with_listener(addr))
+    }
+
+    /// Adds a UDP server listening on `addr`
+    pub fn with_listener(mut self, addr: impl Into<SocketAddr>) -> Self {
+        self.listeners.push(addr.into());
+        self
+    }
+
+    /// Adds a UDP client sending to `addr`, with an [`OscOutbox`] to queue messages in
+    pub fn with_client(mut self, addr: impl Into<SocketAddr>) -> Self {
+        self.clients.push(addr.into());
+        self
+    }
 
-    osc_dispatcher.dispatch(osc_packets, event_writer);
+    /// Sets the receive buffer size of the plugin's UDP servers and clients
+    pub fn with_recv_buffer_size(mut self, buffer_size: usize) -> Self {
+        self.socket_config.buffer_size = buffer_size;
+        self
+    }
+
+    /// Sets the socket options of the plugin's UDP servers and clients, e.g. to join a multicast
+    /// group
+    pub fn with_udp_options(mut self, options: OscUdpOptions) -> Self {
+        self.socket_config.options = options;
+        self
+    }
+
+    /// Receives on the plugin's UDP servers with a dedicated thread each, see
+    /// [`OscUdpServer::with_receive_thread`]
+    pub fn with_receive_thread(mut self, capacity: usize, policy: OscOverflowPolicy) -> Self {
+        self.socket_config.receive_thread = Some((capacity, policy));
+        self
+    }
+
+    /// Sets the schedule packets are received and dispatched in, [`PreUpdate`] by default
+    pub fn in_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.schedules.receive = schedule.intern();
+        self
+    }
+
+    /// Sets the schedule packets are sent in, [`PostUpdate`] by default
+    pub fn with_send_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.schedules.send = schedule.intern();
+        self
+    }
+
+    /// Sets what happens if a socket can't be opened
+    pub fn with_bind_failure_policy(mut self, policy: OscBindFailurePolicy) -> Self {
+        self.bind_failure_policy = policy;
+        self
+    }
+
+    /// Sets the maximum number of address pattern matchers cached by the [`OscDispatcher`]
+    pub fn with_matcher_cache_capacity(mut self, capacity: usize) -> Self {
+        self.matcher_cache_capacity = capacity;
+        self
+    }
 }
 
-impl<A: ToSocketAddrs + Send + Sync + 'static + Clone> Plugin for BevyRoscPlugin<A> {
+impl Plugin for BevyRoscPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(OscDispatcher::default())
-            .add_event::<OscDispatchEvent>()
+        app.insert_resource(OscDispatcher::with_matcher_cache_capacity(
+            self.matcher_cache_capacity,
+        ))
+        .add_event::<OscDispatchEvent>()
+        .add_event::<OscErrorEvent>()
+        .add_event::<OscReplyEvent>()
+        .add_event::<OscSendEvent>()
+        .insert_resource(self.schedules)
+        .init_resource::<OscMatchedMessages>();
+        OscReceivedObserved::track(app);
+
+        if self.schedules.receive == self.schedules.send {
+            app.configure_sets(
+                self.schedules.receive,
+                (OscSet::Receive, OscSet::Dispatch, OscSet::Send).chain(),
+            );
+        } else {
+            app.configure_sets(
+                self.schedules.receive,
+                (OscSet::Receive, OscSet::Dispatch).chain(),
+            );
+            app.configure_sets(self.schedules.send, OscSet::Send);
+        }
+
+        app.add_systems(
+            self.schedules.receive,
+            retry_sockets_system.before(OscSet::Receive),
+        )
+        .add_osc_transport::<OscUdpServer>()
+        .add_osc_transport::<OscUdpClient>()
+        .add_osc_transport::<OscTcpServer>()
+        .add_osc_transport::<OscTcpClient>()
+        .add_osc_transport::<OscLoopback>();
+        #[cfg(unix)]
+        app.add_osc_transport::<OscUnixDatagramServer>()
+            .add_osc_transport::<OscUnixDatagramClient>()
+            .add_osc_transport::<OscUnixStreamServer>()
+            .add_osc_transport::<OscUnixStreamClient>();
+        app.add_osc_method::<SingleAddressOscMethod>()
+            .add_osc_method::<MultiAddressOscMethod>()
+            .add_osc_method::<OscBind>()
+            .add_osc_method::<OscLatestValue>()
+            .add_osc_method::<OscHandler>()
             .add_systems(
-                PreUpdate,
+                self.schedules.receive,
                 (
-                    osc_receive_system,
-                    method_dispatcher_system::<SingleAddressOscMethod>.after(osc_receive_system),
-                    method_dispatcher_system::<MultiAddressOscMethod>.after(osc_receive_system),
+                    osc_bind_system
+                        .in_set(OscSet::Dispatch)
+                        .after(method_dispatcher_system::<OscBind>),
+                    osc_handler_system
+                        .in_set(OscSet::Dispatch)
+                        .after(method_dispatcher_system::<OscHandler>),
+                    osc_unmatched_system.after(OscSet::Dispatch),
                 ),
+            )
+            .add_systems(
+                self.schedules.send,
+                (
+                    osc_send_system,
+                    osc_reply_system,
+                    osc_publish_system,
+                    osc_outbox_system,
+                )
+                    .chain()
+                    .in_set(OscSet::Send),
             );
-        app.world_mut()
-            .spawn(OscUdpServer::new(self.addrs.clone()).unwrap());
+
+        let sockets = self
+            .listeners
+            .iter()
+            .map(|addr| OscSocket::Listener(*addr))
+            .chain(self.clients.iter().map(|addr| OscSocket::Client(*addr)));
+        let mut failed = vec![];
+        for socket in sockets {
+            if let Err(error) = socket.spawn(app.world_mut(), &self.socket_config) {
+                socket.report(app.world_mut(), self.bind_failure_policy, error);
+                failed.push(socket);
+            }
+        }
+
+        if let OscBindFailurePolicy::Retry { interval } = self.bind_failure_policy {
+            if !failed.is_empty() {
+                app.insert_resource(OscPendingSockets {
+                    sockets: failed,
+                    config: self.socket_config.clone(),
+                    interval,
+                    last_attempt: Instant::now(),
+                });
+            }
+        }
     }
 }
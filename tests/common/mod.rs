@@ -0,0 +1,81 @@
+//! Fixtures shared by the integration tests
+#![allow(dead_code)]
+
+use bevy::ecs::system::ScheduleSystem;
+use bevy::prelude::*;
+
+use bevy_rosc::{
+    osc_receive_system, BevyRoscPlugin, OscAppExt, OscDispatchEvent, OscDispatcher, OscErrorEvent,
+    OscLoopback, OscReceivedPacket, OscTransport, OscTransportError,
+};
+use rosc::{OscMessage, OscPacket, OscType};
+use std::net::SocketAddr;
+
+/// An app with the default plugin
+pub fn setup_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(BevyRoscPlugin::default());
+    app
+}
+
+/// Transport that records every packet sent through it
+#[derive(Component, Default)]
+pub struct Recorder(pub Vec<OscPacket>);
+
+impl OscTransport for Recorder {
+    fn recv_packet(&mut self) -> Result<Option<OscReceivedPacket>, OscTransportError> {
+        Ok(None)
+    }
+
+    fn send_packet(
+        &mut self,
+        packet: &OscPacket,
+        _target: Option<SocketAddr>,
+    ) -> Result<(), OscTransportError> {
+        self.0.push(packet.clone());
+        Ok(())
+    }
+}
+
+/// An app with the default plugin and `Recorder` registered as a transport
+pub fn recorder_app() -> App {
+    let mut app = setup_app();
+    app.add_osc_transport::<Recorder>();
+    app
+}
+
+/// An app without the plugin, that receives from `OscLoopback`s and then runs `dispatchers`
+pub fn dispatcher_app<M>(dispatchers: impl IntoScheduleConfigs<ScheduleSystem, M>) -> App {
+    let mut app = App::new();
+    app.add_event::<OscDispatchEvent>()
+        .add_event::<OscErrorEvent>()
+        .insert_resource(OscDispatcher::default())
+        .add_systems(
+            Update,
+            (osc_receive_system::<OscLoopback>, dispatchers).chain(),
+        );
+    app
+}
+
+pub fn message(addr: &str, args: Vec<OscType>) -> OscMessage {
+    OscMessage {
+        addr: addr.into(),
+        args,
+    }
+}
+
+/// Queue a message to be received in the next update, through the app's only `OscLoopback`
+///
+/// The loopback is spawned by the first call.
+pub fn send(app: &mut App, message: OscMessage) {
+    let packet = OscPacket::Message(message);
+    let mut query = app.world_mut().query::<&mut OscLoopback>();
+    match query.iter_mut(app.world_mut()).next() {
+        Some(mut loopback) => loopback.push(packet, None),
+        None => {
+            let mut loopback = OscLoopback::new();
+            loopback.push(packet, None);
+            app.world_mut().spawn(loopback);
+        }
+    }
+}
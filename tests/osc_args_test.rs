@@ -0,0 +1,142 @@
+extern crate bevy_rosc;
+
+use bevy::prelude::*;
+
+use bevy_rosc::{
+    BevyRoscPlugin, OscAppExt, OscArgs, OscArgsError, OscLoopback, OscTransport, TypedOscMethod,
+};
+use rosc::{OscMessage, OscPacket, OscType};
+
+#[derive(OscArgs, Debug, PartialEq)]
+struct Note {
+    pitch: i32,
+    velocity: f32,
+    channel: Option<i32>,
+}
+
+#[derive(OscArgs, Debug, PartialEq)]
+struct Label(String, bool);
+
+#[derive(OscArgs, Debug, PartialEq)]
+struct Range<T> {
+    min: T,
+    max: T,
+}
+
+#[test]
+fn decode_struct() {
+    assert_eq!(
+        Note {
+            pitch: 60,
+            velocity: 0.5,
+            channel: Some(2)
+        },
+        Note::decode_args(&[60.into(), 0.5f32.into(), 2.into()]).unwrap()
+    );
+    // Trailing optional arguments may be left out, ints are converted to floats
+    assert_eq!(
+        Note {
+            pitch: 60,
+            velocity: 1.0,
+            channel: None
+        },
+        Note::decode_args(&[60.into(), 1.into()]).unwrap()
+    );
+    assert_eq!(
+        Label("hi".into(), true),
+        Label::decode_args(&["hi".to_string().into(), true.into()]).unwrap()
+    );
+    assert_eq!(
+        Range { min: 0.0, max: 1.0 },
+        Range::<f64>::decode_args(&[0.into(), 1.0f32.into()]).unwrap()
+    );
+}
+
+#[test]
+fn decode_errors() {
+    assert_eq!(
+        Err(OscArgsError::Missing {
+            index: 1,
+            expected: "float"
+        }),
+        Note::decode_args(&[60.into()])
+    );
+    assert_eq!(
+        Err(OscArgsError::Mismatch {
+            index: 0,
+            expected: "int",
+            found: 'f'
+        }),
+        Note::decode_args(&[60.0f32.into(), 1.0f32.into()])
+    );
+    assert_eq!(
+        Err(OscArgsError::TooMany {
+            expected: 3,
+            found: 4
+        }),
+        Note::decode_args(&[1.into(), 1.into(), 1.into(), 1.into()])
+    );
+}
+
+#[test]
+fn encode_roundtrip() {
+    let note = Note {
+        pitch: 64,
+        velocity: 0.25,
+        channel: None,
+    };
+    let args = note.encode_args();
+    assert_eq!(vec![OscType::Int(64), OscType::Float(0.25)], args);
+    assert_eq!(note, Note::decode_args(&args).unwrap());
+
+    let tuple = (1i64, 2.0f64, "three".to_string());
+    assert_eq!(
+        tuple,
+        <(i64, f64, String)>::decode_args(&tuple.encode_args()).unwrap()
+    );
+}
+
+#[test]
+/// A typed method queues decoded values and keeps messages that failed to decode
+fn typed_osc_method() {
+    let mut app = App::new();
+    app.add_plugins(BevyRoscPlugin::default())
+        .add_osc_method::<TypedOscMethod<Note>>();
+
+    let method = app
+        .world_mut()
+        .spawn(TypedOscMethod::<Note>::new("/note".into()).unwrap())
+        .id();
+
+    let mut loopback = OscLoopback::new();
+    for args in [vec![60.into(), 1.into()], vec!["bad".to_string().into()]] {
+        loopback
+            .send_packet(
+                &OscPacket::Message(OscMessage {
+                    addr: "/note".into(),
+                    args,
+                }),
+                None,
+            )
+            .unwrap();
+    }
+    app.world_mut().spawn(loopback);
+    app.update();
+
+    let mut method = app
+        .world_mut()
+        .get_mut::<TypedOscMethod<Note>>(method)
+        .unwrap();
+    assert_eq!(
+        Some(Note {
+            pitch: 60,
+            velocity: 1.0,
+            channel: None
+        }),
+        method.get_value()
+    );
+    assert_eq!(None, method.get_value());
+    let (message, error) = method.get_error().unwrap();
+    assert_eq!("/note", message.addr);
+    assert!(matches!(error, OscArgsError::Mismatch { index: 0, .. }));
+}
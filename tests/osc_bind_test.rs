@@ -0,0 +1,175 @@
+extern crate bevy_rosc;
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_rosc::{osc_type_tag, OscBind, OscBindError, OscBindTarget, OscErrorEvent};
+use common::{message, send, setup_app};
+use rosc::OscType;
+
+fn bind(address: &str, target: &str) -> OscBind {
+    OscBind::new(address.into(), OscBindTarget::parse(target).unwrap()).unwrap()
+}
+
+fn binding_errors(app: &App) -> Vec<OscBindError> {
+    app.world()
+        .resource::<Events<OscErrorEvent>>()
+        .iter_current_update_events()
+        .filter_map(|event| match event {
+            OscErrorEvent::Binding { error, .. } => Some(error.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+/// Arguments are converted and written into single fields and whole structs
+fn bind_fields() {
+    let mut app = setup_app();
+    app.register_type::<Transform>();
+
+    let x = app
+        .world_mut()
+        .spawn((Transform::default(), bind("/x", "Transform.translation.x")))
+        .id();
+    let scale = app
+        .world_mut()
+        .spawn((Transform::default(), bind("/scale", "Transform.scale")))
+        .id();
+    send(&mut app, message("/x", vec![OscType::Int(3)]));
+    send(
+        &mut app,
+        message(
+            "/scale",
+            vec![OscType::Float(1.0), OscType::Double(2.0), OscType::Int(3)],
+        ),
+    );
+
+    app.update();
+
+    assert!(binding_errors(&app).is_empty());
+    assert_eq!(
+        Vec3::new(3.0, 0.0, 0.0),
+        app.world().get::<Transform>(x).unwrap().translation
+    );
+    assert_eq!(
+        Vec3::new(1.0, 2.0, 3.0),
+        app.world().get::<Transform>(scale).unwrap().scale
+    );
+}
+
+#[test]
+/// Messages that don't fit the target are reported
+fn bind_errors() {
+    let mut app = setup_app();
+    app.register_type::<Transform>();
+
+    app.world_mut()
+        .spawn((Transform::default(), bind("/mismatch", "Transform.scale")));
+    app.world_mut()
+        .spawn((Transform::default(), bind("/field", "Transform.nothing")));
+    app.world_mut().spawn(bind("/component", "Transform.scale"));
+    app.world_mut().spawn(bind("/unknown", "Unknown.field"));
+    send(
+        &mut app,
+        message("/mismatch", vec!["big".to_string().into()]),
+    );
+    send(&mut app, message("/field", vec![1.into()]));
+    send(&mut app, message("/component", vec![1.into()]));
+    send(&mut app, message("/unknown", vec![1.into()]));
+
+    app.update();
+
+    let errors = binding_errors(&app);
+    assert_eq!(4, errors.len());
+    assert!(errors.contains(&OscBindError::Mismatch {
+        expected: "glam::Vec3".into(),
+        found: "s".into()
+    }));
+    assert!(errors
+        .iter()
+        .any(|error| matches!(error, OscBindError::MissingField(_))));
+    assert!(errors.contains(&OscBindError::MissingComponent("Transform".into())));
+    assert!(errors.contains(&OscBindError::UnknownComponent("Unknown".into())));
+}
+
+#[test]
+/// A mismatch in any argument leaves the whole field unchanged
+fn bind_mismatch_unchanged() {
+    let mut app = setup_app();
+    app.register_type::<Transform>();
+
+    let scale = app
+        .world_mut()
+        .spawn((Transform::default(), bind("/scale", "Transform.scale")))
+        .id();
+    send(
+        &mut app,
+        message(
+            "/scale",
+            vec![2.0f32.into(), 3.0f32.into(), "oops".to_string().into()],
+        ),
+    );
+
+    app.update();
+
+    assert_eq!(
+        vec![OscBindError::Mismatch {
+            expected: "glam::Vec3".into(),
+            found: "ffs".into()
+        }],
+        binding_errors(&app)
+    );
+    assert_eq!(
+        Vec3::ONE,
+        app.world().get::<Transform>(scale).unwrap().scale
+    );
+}
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct Counter {
+    value: u8,
+}
+
+#[test]
+/// Integer fields only accept whole numbers that fit
+fn bind_integer_range() {
+    let mut app = setup_app();
+    app.register_type::<Counter>();
+
+    let counter = app
+        .world_mut()
+        .spawn((Counter::default(), bind("/count", "Counter.value")))
+        .id();
+
+    for arg in [
+        OscType::Int(300),
+        OscType::Int(-1),
+        OscType::Float(2.5),
+        OscType::Float(f32::NAN),
+        OscType::Double(f64::INFINITY),
+    ] {
+        let found = osc_type_tag(&arg).to_string();
+        send(&mut app, message("/count", vec![arg]));
+        app.update();
+        assert_eq!(
+            vec![OscBindError::Mismatch {
+                expected: std::any::type_name::<u8>().into(),
+                found
+            }],
+            binding_errors(&app)
+        );
+    }
+    assert_eq!(0, app.world().get::<Counter>(counter).unwrap().value);
+
+    send(&mut app, message("/count", vec![OscType::Int(255)]));
+    app.update();
+    assert_eq!(255, app.world().get::<Counter>(counter).unwrap().value);
+
+    send(&mut app, message("/count", vec![OscType::Float(7.0)]));
+    app.update();
+    assert!(binding_errors(&app).is_empty());
+    assert_eq!(7, app.world().get::<Counter>(counter).unwrap().value);
+}
@@ -1,12 +1,18 @@
 extern crate bevy_rosc;
 
+use bevy::ecs::system::RunSystemOnce;
 use bevy::prelude::*;
 
 use bevy_rosc::OscDispatchEvent;
 use bevy_rosc::OscDispatcher;
+use bevy_rosc::OscErrorEvent;
+use bevy_rosc::OscLatePolicy;
+use bevy_rosc::OscMessageMeta;
+use bevy_rosc::OscScheduleError;
 use rosc::address::Matcher;
 use rosc::OscPacket;
 use rosc::{OscBundle, OscMessage, OscTime};
+use std::time::{Duration, SystemTime};
 
 // Resource wrapper utility to use scalar types as resources
 #[derive(Resource, Deref, DerefMut)]
@@ -84,7 +90,7 @@ fn dispatch_bundle(mut disp: ResMut<OscDispatcher>, event_writer: EventWriter<Os
 fn check_event_bundle(
     mut event_reader: EventReader<OscDispatchEvent>,
 
-    mut received_msgs: ResMut<Wrapper<Vec<(Matcher, OscMessage)>>>,
+    mut received_msgs: ResMut<Wrapper<Vec<(Matcher, OscMessage, OscMessageMeta)>>>,
 ) {
     // Get all messages included in the event
     received_msgs.0 = match event_reader.read().next() {
@@ -101,7 +107,7 @@ fn dispatch_osc_bundle() {
     app.add_event::<OscDispatchEvent>();
 
     // Messages that were included in the event
-    let msgs: Wrapper<Vec<(Matcher, OscMessage)>> = Wrapper(vec![]);
+    let msgs: Wrapper<Vec<(Matcher, OscMessage, OscMessageMeta)>> = Wrapper(vec![]);
     app.insert_resource(msgs);
 
     app.add_systems(Update, (
@@ -114,9 +120,178 @@ fn dispatch_osc_bundle() {
     app.update();
 
     // Resource is set to true if event was received
-    let received_msgs = app.world_mut().resource::<Wrapper<Vec<(Matcher, OscMessage)>>>();
+    let received_msgs = app.world_mut().resource::<Wrapper<Vec<(Matcher, OscMessage, OscMessageMeta)>>>();
     assert_eq!(3, received_msgs.0.len());
     assert_eq!("/entity1/value", received_msgs[0].1.addr);
     assert_eq!("/entity2/value", received_msgs[1].1.addr);
     assert_eq!("/entity3/value", received_msgs[2].1.addr);
 }
+
+/// Dispatch all packets queued in the `Wrapper<Vec<OscPacket>>` resource
+fn dispatch_queued(
+    mut disp: ResMut<OscDispatcher>,
+    mut packets: ResMut<Wrapper<Vec<OscPacket>>>,
+    event_writer: EventWriter<OscDispatchEvent>,
+) {
+    disp.dispatch(packets.drain(..).collect(), event_writer);
+}
+
+fn setup_queued_dispatch(dispatcher: OscDispatcher) -> App {
+    let mut app = App::new();
+    app.add_event::<OscDispatchEvent>();
+    app.insert_resource(dispatcher);
+    app.insert_resource(Wrapper(Vec::<OscPacket>::new()));
+    app.insert_resource(Wrapper(Vec::<(Matcher, OscMessage, OscMessageMeta)>::new()));
+    app.add_systems(Update, (dispatch_queued, check_event_bundle).chain());
+    app
+}
+
+fn bundle_at(timetag: OscTime, addr: &str) -> OscPacket {
+    OscPacket::Bundle(OscBundle {
+        timetag,
+        content: vec![OscPacket::Message(OscMessage {
+            addr: addr.to_string(),
+            args: vec![],
+        })],
+    })
+}
+
+fn osc_time_from_now(offset: Duration, later: bool) -> OscTime {
+    let now = SystemTime::now();
+    let time = if later { now + offset } else { now - offset };
+    OscTime::try_from(time).unwrap()
+}
+
+fn received_addrs(app: &App) -> Vec<String> {
+    app.world()
+        .resource::<Wrapper<Vec<(Matcher, OscMessage, OscMessageMeta)>>>()
+        .iter()
+        .map(|(_, msg, _)| msg.addr.clone())
+        .collect()
+}
+
+#[test]
+/// Bundles with a timetag in the future are only delivered once their time has come
+fn dispatch_scheduled_bundle() {
+    let mut app = setup_queued_dispatch(OscDispatcher::default());
+
+    let timetag = osc_time_from_now(Duration::from_millis(100), true);
+    app.world_mut()
+        .resource_mut::<Wrapper<Vec<OscPacket>>>()
+        .extend([
+            bundle_at(timetag, "/later"),
+            bundle_at(OscTime::from((0, 1)), "/now"),
+        ]);
+
+    app.update();
+    assert_eq!(vec!["/now".to_string()], received_addrs(&app));
+    assert_eq!(1, app.world().resource::<OscDispatcher>().scheduled_len());
+
+    std::thread::sleep(Duration::from_millis(150));
+    app.update();
+    assert_eq!(vec!["/later".to_string()], received_addrs(&app));
+    assert_eq!(0, app.world().resource::<OscDispatcher>().scheduled_len());
+
+    let received = app
+        .world()
+        .resource::<Wrapper<Vec<(Matcher, OscMessage, OscMessageMeta)>>>();
+    assert_eq!(Some(timetag), received[0].2.timetag);
+    assert!(!received[0].2.late);
+}
+
+#[test]
+/// Bundles with a timetag in the past are handled according to the late policy
+fn dispatch_late_bundle() {
+    let timetag = osc_time_from_now(Duration::from_secs(10), false);
+
+    let mut app = setup_queued_dispatch(OscDispatcher::with_late_policy(OscLatePolicy::Drop));
+    app.world_mut()
+        .resource_mut::<Wrapper<Vec<OscPacket>>>()
+        .push(bundle_at(timetag, "/late"));
+    app.update();
+    assert!(received_addrs(&app).is_empty());
+
+    let mut app = setup_queued_dispatch(OscDispatcher::with_late_policy(OscLatePolicy::Flag));
+    app.world_mut()
+        .resource_mut::<Wrapper<Vec<OscPacket>>>()
+        .extend([
+            bundle_at(timetag, "/late"),
+            bundle_at(OscTime::from((0, 1)), "/now"),
+        ]);
+    app.update();
+    let received = app
+        .world()
+        .resource::<Wrapper<Vec<(Matcher, OscMessage, OscMessageMeta)>>>();
+    assert_eq!(2, received.len());
+    assert!(received[0].2.late);
+    assert!(!received[1].2.late);
+}
+
+#[test]
+/// Future bundles are dropped and reported when the schedule is full or they are too far ahead
+fn schedule_bounded() {
+    let mut dispatcher = OscDispatcher::default();
+    dispatcher.set_max_scheduled(1);
+    dispatcher.set_max_schedule_horizon(Duration::from_secs(10));
+    let mut app = App::new();
+    app.add_event::<OscDispatchEvent>();
+    app.insert_resource(dispatcher);
+
+    let soon = osc_time_from_now(Duration::from_secs(1), true);
+    let packets = vec![
+        bundle_at(osc_time_from_now(Duration::from_secs(20), true), "/far"),
+        bundle_at(soon, "/first"),
+        bundle_at(soon, "/second"),
+    ];
+    let errors = app
+        .world_mut()
+        .run_system_once(
+            move |mut disp: ResMut<OscDispatcher>, event_writer: EventWriter<OscDispatchEvent>| {
+                disp.dispatch(packets.clone(), event_writer)
+            },
+        )
+        .unwrap();
+
+    assert!(matches!(
+        errors[..],
+        [
+            OscErrorEvent::Schedule {
+                error: OscScheduleError::TooFarAhead { .. },
+                ..
+            },
+            OscErrorEvent::Schedule {
+                error: OscScheduleError::Full { capacity: 1 },
+                timetag,
+                ..
+            },
+        ] if timetag == soon
+    ));
+    assert_eq!(1, app.world().resource::<OscDispatcher>().scheduled_len());
+}
+
+#[test]
+/// The matcher cache never grows beyond its capacity and evicts the least recently used matchers
+fn matcher_cache_bounded() {
+    let mut app = setup_queued_dispatch(OscDispatcher::with_matcher_cache_capacity(2));
+
+    let packets = ["/a", "/b", "/a", "/c", "/b"].map(|addr| {
+        OscPacket::Message(OscMessage {
+            addr: addr.to_string(),
+            args: vec![],
+        })
+    });
+    app.world_mut()
+        .resource_mut::<Wrapper<Vec<OscPacket>>>()
+        .extend(packets);
+    app.update();
+
+    // All messages are still dispatched
+    assert_eq!(5, received_addrs(&app).len());
+
+    let stats = app.world().resource::<OscDispatcher>().matcher_cache_stats();
+    assert_eq!(1, stats.hits);
+    assert_eq!(4, stats.misses);
+    assert_eq!(2, stats.evictions);
+    assert_eq!(2, stats.len);
+    assert_eq!(2, stats.capacity);
+}
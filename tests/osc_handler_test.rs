@@ -0,0 +1,69 @@
+extern crate bevy_rosc;
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_rosc::OscHandler;
+use common::{message, send, setup_app};
+use rosc::{OscMessage, OscType};
+
+#[derive(Component, Debug, PartialEq)]
+struct Volume(f32);
+
+#[derive(Resource, Default)]
+struct Received(Vec<OscMessage>);
+
+#[test]
+/// Closures get the commands of the handler's entity
+fn closure_handler() {
+    let mut app = setup_app();
+    let entity = app
+        .world_mut()
+        .spawn(
+            OscHandler::new("/volume".into(), |message, entity| {
+                if let Some(OscType::Float(value)) = message.args.first() {
+                    entity.insert(Volume(*value));
+                }
+            })
+            .unwrap()
+            .with_address("/gain".into())
+            .unwrap(),
+        )
+        .id();
+
+    send(&mut app, message("/volume", vec![0.5f32.into()]));
+    app.update();
+    assert_eq!(Some(&Volume(0.5)), app.world().get::<Volume>(entity));
+
+    send(&mut app, message("/gain", vec![0.8f32.into()]));
+    app.update();
+    assert_eq!(Some(&Volume(0.8)), app.world().get::<Volume>(entity));
+}
+
+#[test]
+/// One-shot systems are run with every received message
+fn system_handler() {
+    let mut app = setup_app();
+    app.init_resource::<Received>();
+    let system = app.world_mut().register_system(
+        |In(message): In<OscMessage>, mut received: ResMut<Received>| received.0.push(message),
+    );
+    app.world_mut()
+        .spawn(OscHandler::from_system("/fader/1".into(), system).unwrap());
+
+    send(&mut app, message("/fader/1", vec![0.1f32.into()]));
+    send(&mut app, message("/fader/*", vec![0.2f32.into()]));
+    send(&mut app, message("/other", vec![0.3f32.into()]));
+    app.update();
+
+    let mut received = app.world_mut().resource_mut::<Received>();
+    received.0.sort_by(|a, b| a.addr.cmp(&b.addr));
+    assert_eq!(
+        vec![
+            message("/fader/*", vec![0.2f32.into()]),
+            message("/fader/1", vec![0.1f32.into()])
+        ],
+        received.0
+    );
+}
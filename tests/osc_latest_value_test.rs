@@ -0,0 +1,99 @@
+extern crate bevy_rosc;
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_rosc::{OscLatestValue, OscMethod};
+use common::{message, send, setup_app};
+use rosc::address::Matcher;
+use rosc::OscMessage;
+
+#[derive(Resource, Default)]
+struct Updates(Vec<Option<OscMessage>>);
+
+fn count_updates(
+    mut query: Query<&mut OscLatestValue, Changed<OscLatestValue>>,
+    mut updates: ResMut<Updates>,
+) {
+    for mut value in query.iter_mut() {
+        let message = value.read().cloned();
+        updates.0.push(message);
+    }
+}
+
+fn fader(value: f32) -> OscMessage {
+    message("/fader", vec![value.into()])
+}
+
+#[test]
+/// Only the latest message is kept, and `Changed` only matches when a message arrived
+fn keep_latest() {
+    let mut app = setup_app();
+    app.init_resource::<Updates>()
+        .add_systems(Update, count_updates);
+    let entity = app
+        .world_mut()
+        .spawn(OscLatestValue::new("/fader".into()).unwrap())
+        .id();
+    app.world_mut()
+        .spawn(OscLatestValue::new("/other".into()).unwrap());
+    app.update();
+    app.world_mut().resource_mut::<Updates>().0.clear();
+
+    for value in [0.1, 0.2, 0.3] {
+        send(&mut app, fader(value));
+    }
+    app.update();
+    app.update();
+
+    assert_eq!(vec![Some(fader(0.3))], app.world().resource::<Updates>().0);
+    let value = app.world().get::<OscLatestValue>(entity).unwrap();
+    assert!(!value.is_dirty());
+    assert!(value.last_changed().is_some());
+    assert_eq!(Some(&fader(0.3)), value.latest());
+}
+
+#[test]
+/// A method with several addresses keeps the latest message per address, also for patterns
+fn keep_latest_per_address() {
+    let mut value =
+        OscLatestValue::with_addresses(vec!["/mixer/1".into(), "/mixer/2".into()]).unwrap();
+    assert!(!value.is_dirty());
+    assert_eq!(None, value.read());
+
+    value.receive_message(message("/mixer/1", vec![0.5f32.into()]));
+    value.receive_message(message("/mixer/*", vec![1.0f32.into()]));
+    value.receive_message(message("/mixer/2", vec![0.0f32.into()]));
+
+    assert!(value.is_dirty());
+    assert_eq!(
+        Some(&message("/mixer/*", vec![1.0f32.into()])),
+        value.get("/mixer/1")
+    );
+    assert_eq!(
+        Some(&message("/mixer/2", vec![0.0f32.into()])),
+        value.get("/mixer/2")
+    );
+    assert_eq!(
+        Some(&message("/mixer/2", vec![0.0f32.into()])),
+        value.read()
+    );
+    assert!(!value.is_dirty());
+    assert_eq!(2, value.iter().count());
+}
+
+#[test]
+/// The dispatcher's matcher updates every address it matches
+fn match_with_matcher() {
+    let mut value =
+        OscLatestValue::with_addresses(vec!["/mixer/1".into(), "/mixer/2".into()]).unwrap();
+    let fader = message("/mixer/{1,2}", vec![0.5f32.into()]);
+
+    assert!(!value.match_message(&Matcher::new("/other").unwrap(), &fader));
+    assert!(!value.is_dirty());
+    assert!(value.match_message(&Matcher::new(&fader.addr).unwrap(), &fader));
+
+    assert_eq!(Some(&fader), value.get("/mixer/1"));
+    assert_eq!(Some(&fader), value.get("/mixer/2"));
+}
@@ -0,0 +1,212 @@
+extern crate bevy_rosc;
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_rosc::{
+    method_dispatcher_system, MultiAddressOscMethod, OscAddressSpace, OscMessageMeta, OscMethod,
+    OscOverflowPolicy, OscQueuePolicy, SingleAddressOscMethod,
+};
+use common::{dispatcher_app, message, send};
+use rosc::address::{Matcher, OscAddress};
+use rosc::{OscMessage, OscType};
+
+/// Queue messages with the given address patterns and run a single update
+fn dispatch(app: &mut App, addrs: &[&str]) {
+    for addr in addrs {
+        send(app, message(addr, vec![]));
+    }
+    app.update();
+}
+
+/// Drain all messages received by an entity's `SingleAddressOscMethod`
+fn received(app: &mut App, entity: Entity) -> Vec<String> {
+    let mut osc_method = app
+        .world_mut()
+        .get_mut::<SingleAddressOscMethod>(entity)
+        .unwrap();
+    std::iter::from_fn(|| osc_method.get_message())
+        .map(|msg| msg.addr)
+        .collect()
+}
+
+#[test]
+/// Messages are delivered through the address space index, both for literal and wildcard patterns,
+/// and the index follows components being added and removed
+fn address_space_dispatch() {
+    let mut app = dispatcher_app((
+        method_dispatcher_system::<SingleAddressOscMethod>,
+        method_dispatcher_system::<MultiAddressOscMethod>,
+    ));
+
+    let ab = app
+        .world_mut()
+        .spawn(SingleAddressOscMethod::new("/a/b".into()).unwrap())
+        .id();
+    // The first update creates the index
+    dispatch(&mut app, &["/a/b"]);
+    assert_eq!(vec!["/a/b"], received(&mut app, ab));
+    assert_eq!(
+        1,
+        app.world()
+            .resource::<OscAddressSpace<SingleAddressOscMethod>>()
+            .len()
+    );
+
+    let ac = app
+        .world_mut()
+        .spawn(SingleAddressOscMethod::new("/a/c".into()).unwrap())
+        .id();
+    let multi = app
+        .world_mut()
+        .spawn(MultiAddressOscMethod::new(vec!["/a/b".into(), "/x/b".into()]).unwrap())
+        .id();
+
+    dispatch(&mut app, &["/a/b", "/a/*", "/{a,x}/?", "/a", "/a/b/c"]);
+    assert_eq!(vec!["/a/b", "/a/*", "/{a,x}/?"], received(&mut app, ab));
+    assert_eq!(vec!["/a/*", "/{a,x}/?"], received(&mut app, ac));
+
+    // Messages matching several addresses of a method are only received once
+    let mut multi_method = app
+        .world_mut()
+        .get_mut::<MultiAddressOscMethod>(multi)
+        .unwrap();
+    let multi_received: Vec<String> = std::iter::from_fn(|| multi_method.get_message())
+        .map(|msg| msg.addr)
+        .collect();
+    assert_eq!(vec!["/a/b", "/a/*", "/{a,x}/?"], multi_received);
+
+    app.world_mut().despawn(ab);
+    assert_eq!(
+        1,
+        app.world()
+            .resource::<OscAddressSpace<SingleAddressOscMethod>>()
+            .len()
+    );
+    dispatch(&mut app, &["/a/*"]);
+    assert_eq!(vec!["/a/*"], received(&mut app, ac));
+}
+
+#[test]
+/// Bounded queues drop messages according to their policy and count them
+fn queue_policy() {
+    for (policy, expected, dropped) in [
+        (OscQueuePolicy::Unbounded, vec!["/0", "/1", "/2", "/3"], 0),
+        (
+            OscQueuePolicy::bounded(2, OscOverflowPolicy::DropOldest),
+            vec!["/2", "/3"],
+            2,
+        ),
+        (
+            OscQueuePolicy::bounded(2, OscOverflowPolicy::DropNewest),
+            vec!["/0", "/1"],
+            2,
+        ),
+        (OscQueuePolicy::keep_latest(), vec!["/3"], 3),
+    ] {
+        let mut osc_method = SingleAddressOscMethod::new("/a".into())
+            .unwrap()
+            .with_queue_policy(policy);
+        for i in 0..4 {
+            osc_method.receive_message(OscMessage {
+                addr: format!("/{}", i),
+                args: vec![],
+            });
+        }
+
+        assert_eq!(policy, osc_method.queue_policy());
+        assert_eq!(dropped, osc_method.dropped_messages());
+        let messages: Vec<String> = std::iter::from_fn(|| osc_method.get_message())
+            .map(|msg| msg.addr)
+            .collect();
+        assert_eq!(expected, messages);
+    }
+}
+
+/// Method that only accepts messages with arguments
+#[derive(Component)]
+struct WithArgs {
+    received: usize,
+}
+
+impl OscMethod for WithArgs {
+    fn get_addresses(&self) -> Vec<OscAddress> {
+        vec![OscAddress::new("/a".into()).unwrap()]
+    }
+    fn receive_message(&mut self, _osc_message: OscMessage) {
+        self.received += 1;
+    }
+    fn match_message_with_meta(
+        &mut self,
+        matcher: &Matcher,
+        message: &OscMessage,
+        meta: &OscMessageMeta,
+    ) -> bool {
+        if message.args.is_empty() || !matcher.match_address(&self.get_addresses()[0]) {
+            return false;
+        }
+        self.receive_message_with_meta(message.clone(), meta);
+        true
+    }
+}
+
+#[test]
+/// Overrides of `match_message_with_meta` are called with and without the address space index
+fn match_override() {
+    let mut app = dispatcher_app(method_dispatcher_system::<WithArgs>);
+    let entity = app.world_mut().spawn(WithArgs { received: 0 }).id();
+
+    // The first update dispatches without the index, the second one with it
+    for received in [1, 2] {
+        send(&mut app, message("/a", vec![]));
+        send(&mut app, message("/a", vec![OscType::Int(1)]));
+        app.update();
+        assert_eq!(
+            received,
+            app.world().get::<WithArgs>(entity).unwrap().received
+        );
+    }
+    assert!(app.world().contains_resource::<OscAddressSpace<WithArgs>>());
+}
+
+/// Method that only overrides `match_message`, like methods written before metadata existed
+#[derive(Component)]
+struct IntsOnly {
+    received: usize,
+}
+
+impl OscMethod for IntsOnly {
+    fn get_addresses(&self) -> Vec<OscAddress> {
+        vec![OscAddress::new("/a".into()).unwrap()]
+    }
+    fn receive_message(&mut self, _osc_message: OscMessage) {
+        self.received += 1;
+    }
+    fn match_message(&mut self, matcher: &Matcher, message: &OscMessage) -> bool {
+        if !matches!(message.args[..], [OscType::Int(_)])
+            || !matcher.match_address(&self.get_addresses()[0])
+        {
+            return false;
+        }
+        self.receive_message(message.clone());
+        true
+    }
+}
+
+#[test]
+/// Overrides of `match_message` still filter dispatched messages
+fn match_message_override() {
+    let mut app = dispatcher_app(method_dispatcher_system::<IntsOnly>);
+    let entity = app.world_mut().spawn(IntsOnly { received: 0 }).id();
+
+    for received in [1, 2] {
+        send(&mut app, message("/a", vec![OscType::Float(1.0)]));
+        send(&mut app, message("/a", vec![OscType::Int(1)]));
+        app.update();
+        assert_eq!(
+            received,
+            app.world().get::<IntsOnly>(entity).unwrap().received
+        );
+    }
+}
@@ -0,0 +1,94 @@
+extern crate bevy_rosc;
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_rosc::{OscErrorEvent, OscOutbox};
+use common::{recorder_app, Recorder};
+use rosc::encoder::encode;
+use rosc::{OscMessage, OscPacket, OscType};
+use std::time::{Duration, SystemTime};
+
+/// Spawns a recorder with `outbox`
+fn spawn_outbox(outbox: OscOutbox) -> (App, Entity) {
+    let mut app = recorder_app();
+    let entity = app.world_mut().spawn((Recorder::default(), outbox)).id();
+    (app, entity)
+}
+
+fn message(index: i32, size: usize) -> OscMessage {
+    OscMessage {
+        addr: "/data".into(),
+        args: vec![OscType::Int(index), OscType::Blob(vec![0; size])],
+    }
+}
+
+fn send_errors(app: &App) -> usize {
+    app.world()
+        .resource::<Events<OscErrorEvent>>()
+        .iter_current_update_events()
+        .filter(|event| matches!(event, OscErrorEvent::Send { .. }))
+        .count()
+}
+
+#[test]
+/// Messages are bundled in order without exceeding the maximum packet size
+fn split_into_bundles() {
+    let (mut app, entity) = spawn_outbox(OscOutbox::new().with_max_packet_size(256));
+    {
+        let mut outbox = app.world_mut().get_mut::<OscOutbox>(entity).unwrap();
+        for i in 0..10 {
+            outbox.push(message(i, 64));
+        }
+        // Too large to fit into any packet
+        outbox.push(message(10, 512));
+    }
+    app.update();
+
+    let packets = app.world().get::<Recorder>(entity).unwrap().0.clone();
+    assert!(packets.len() > 1);
+    let mut indices = vec![];
+    for packet in &packets {
+        assert!(encode(packet).unwrap().len() <= 256);
+        let OscPacket::Bundle(bundle) = packet else {
+            panic!("Expected a bundle");
+        };
+        for content in &bundle.content {
+            let OscPacket::Message(message) = content else {
+                panic!("Expected a message");
+            };
+            indices.push(message.args[0].clone().int().unwrap());
+        }
+    }
+    assert_eq!((0..10).collect::<Vec<_>>(), indices);
+    assert_eq!(1, send_errors(&app));
+    assert!(app.world().get::<OscOutbox>(entity).unwrap().is_empty());
+
+    // Nothing is sent when the outbox is empty
+    app.update();
+    assert_eq!(
+        packets.len(),
+        app.world().get::<Recorder>(entity).unwrap().0.len()
+    );
+}
+
+#[test]
+/// Bundles are stamped with the current time plus the latency
+fn latency_timetag() {
+    let (mut app, entity) = spawn_outbox(OscOutbox::new().with_latency(Duration::from_millis(100)));
+    app.world_mut()
+        .get_mut::<OscOutbox>(entity)
+        .unwrap()
+        .push(message(0, 4));
+    let before = SystemTime::now();
+    app.update();
+
+    let packets = &app.world().get::<Recorder>(entity).unwrap().0;
+    let [OscPacket::Bundle(bundle)] = packets.as_slice() else {
+        panic!("Expected a single bundle");
+    };
+    let timetag = SystemTime::from(bundle.timetag);
+    assert!(timetag >= before + Duration::from_millis(99));
+    assert!(timetag <= SystemTime::now() + Duration::from_millis(100));
+}
@@ -0,0 +1,248 @@
+extern crate bevy_rosc;
+
+use bevy::prelude::*;
+
+use bevy_rosc::{
+    osc_method_dispatcher, BevyRoscPlugin, OscAppExt, OscBindFailurePolicy, OscErrorEvent,
+    OscLoopback, OscMessageMeta, OscMethod, OscSet, OscTransport, OscUdpClient, OscUdpServer,
+    SingleAddressOscMethod,
+};
+use rosc::address::{Matcher, OscAddress};
+use rosc::{OscMessage, OscPacket};
+use std::net::{SocketAddr, UdpSocket};
+use std::time::Duration;
+
+/// Returns a socket occupying a free local port
+fn occupied_port() -> (UdpSocket, SocketAddr) {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = socket.local_addr().unwrap();
+    (socket, addr)
+}
+
+fn bind_errors(app: &App) -> Vec<SocketAddr> {
+    app.world()
+        .resource::<Events<OscErrorEvent>>()
+        .iter_current_update_events()
+        .filter_map(|event| match event {
+            OscErrorEvent::Bind { addr, .. } => Some(*addr),
+            _ => None,
+        })
+        .collect()
+}
+
+fn count<T: Component>(app: &mut App) -> usize {
+    app.world_mut().query::<&T>().iter(app.world()).count()
+}
+
+#[test]
+/// The plugin opens every configured listener and client
+fn multiple_sockets() {
+    let mut app = App::new();
+    app.add_plugins(
+        BevyRoscPlugin::default()
+            .with_listener(([127, 0, 0, 1], 0))
+            .with_listener(([127, 0, 0, 1], 0))
+            .with_client(([127, 0, 0, 1], 9000)),
+    );
+    app.update();
+
+    assert_eq!(2, count::<OscUdpServer>(&mut app));
+    assert_eq!(1, count::<OscUdpClient>(&mut app));
+    assert!(bind_errors(&app).is_empty());
+}
+
+#[test]
+/// A port that is already in use is reported instead of crashing the app
+fn bind_failure_is_reported() {
+    let (_socket, addr) = occupied_port();
+
+    let mut app = App::new();
+    app.add_plugins(
+        BevyRoscPlugin::default()
+            .with_listener(addr)
+            .with_listener(([127, 0, 0, 1], 0)),
+    );
+
+    assert_eq!(vec![addr], bind_errors(&app));
+    app.update();
+    assert_eq!(1, count::<OscUdpServer>(&mut app));
+}
+
+#[test]
+#[should_panic(expected = "Failed to open OSC listener")]
+fn bind_failure_panics() {
+    let (_socket, addr) = occupied_port();
+
+    App::new().add_plugins(
+        BevyRoscPlugin::default()
+            .with_listener(addr)
+            .with_bind_failure_policy(OscBindFailurePolicy::Panic),
+    );
+}
+
+#[test]
+/// With the retry policy, the listener is opened once the port is free again
+fn bind_failure_retry() {
+    let (socket, addr) = occupied_port();
+
+    let mut app = App::new();
+    app.add_plugins(
+        BevyRoscPlugin::default()
+            .with_listener(addr)
+            .with_bind_failure_policy(OscBindFailurePolicy::Retry {
+                interval: Duration::ZERO,
+            }),
+    );
+
+    app.update();
+    assert_eq!(0, count::<OscUdpServer>(&mut app));
+    assert_eq!(vec![addr], bind_errors(&app));
+
+    drop(socket);
+    app.update();
+    assert_eq!(1, count::<OscUdpServer>(&mut app));
+
+    app.update();
+    assert_eq!(1, count::<OscUdpServer>(&mut app));
+}
+
+#[derive(Component)]
+struct CountingMethod {
+    address: OscAddress,
+    count: usize,
+}
+
+impl OscMethod for CountingMethod {
+    fn get_addresses(&self) -> Vec<OscAddress> {
+        vec![self.address.clone()]
+    }
+
+    fn receive_message(&mut self, _osc_message: OscMessage) {}
+
+    fn receive_message_with_meta(&mut self, _osc_message: OscMessage, _meta: &OscMessageMeta) {
+        self.count += 1;
+    }
+
+    fn match_message_with_meta(
+        &mut self,
+        matcher: &Matcher,
+        message: &OscMessage,
+        meta: &OscMessageMeta,
+    ) -> bool {
+        self.match_addresses_with_meta(matcher, message, meta)
+    }
+}
+
+#[derive(Resource, Default)]
+struct Seen {
+    single: usize,
+    custom: usize,
+}
+
+fn record_deliveries(
+    mut single: Query<&mut SingleAddressOscMethod>,
+    custom: Query<&CountingMethod>,
+    mut seen: ResMut<Seen>,
+) {
+    for mut method in single.iter_mut() {
+        while method.get_message().is_some() {
+            seen.single += 1;
+        }
+    }
+    seen.custom = custom.iter().map(|method| method.count).sum();
+}
+
+#[test]
+/// Systems ordered after the dispatch set see messages in the frame they are received, including
+/// the ones delivered to custom methods added with `osc_method_dispatcher`
+fn dispatch_set_ordering() {
+    let mut app = App::new();
+    app.add_plugins(BevyRoscPlugin::default())
+        .init_resource::<Seen>()
+        .add_systems(PreUpdate, record_deliveries.after(OscSet::Dispatch))
+        .add_systems(PreUpdate, osc_method_dispatcher::<CountingMethod>());
+
+    app.world_mut()
+        .spawn(SingleAddressOscMethod::new("/set".into()).unwrap());
+    app.world_mut().spawn(CountingMethod {
+        address: OscAddress::new("/set".into()).unwrap(),
+        count: 0,
+    });
+    let mut loopback = OscLoopback::new();
+    loopback
+        .send_packet(
+            &OscPacket::Message(OscMessage {
+                addr: "/set".into(),
+                args: vec![],
+            }),
+            None,
+        )
+        .unwrap();
+    app.world_mut().spawn(loopback);
+
+    app.update();
+
+    let seen = app.world().resource::<Seen>();
+    assert_eq!(1, seen.single);
+    assert_eq!(1, seen.custom);
+}
+
+/// Spawns a loopback transport with a single message for `addr`
+fn spawn_message(app: &mut App, addr: &str) {
+    let mut loopback = OscLoopback::new();
+    loopback
+        .send_packet(
+            &OscPacket::Message(OscMessage {
+                addr: addr.into(),
+                args: vec![],
+            }),
+            None,
+        )
+        .unwrap();
+    app.world_mut().spawn(loopback);
+}
+
+#[test]
+/// Registering a method twice adds its dispatcher only once
+fn add_osc_method_deduplicates() {
+    let mut app = App::new();
+    app.add_plugins(BevyRoscPlugin::default())
+        .add_osc_method::<CountingMethod>()
+        .add_osc_method::<CountingMethod>()
+        .add_osc_method::<SingleAddressOscMethod>();
+
+    let method = app
+        .world_mut()
+        .spawn(CountingMethod {
+            address: OscAddress::new("/twice".into()).unwrap(),
+            count: 0,
+        })
+        .id();
+    spawn_message(&mut app, "/twice");
+    app.update();
+
+    assert_eq!(1, app.world().get::<CountingMethod>(method).unwrap().count);
+}
+
+#[derive(Component, Reflect)]
+struct ReflectedMethod {
+    address: String,
+}
+
+impl OscMethod for ReflectedMethod {
+    fn get_addresses(&self) -> Vec<OscAddress> {
+        vec![OscAddress::new(self.address.clone()).unwrap()]
+    }
+
+    fn receive_message(&mut self, _osc_message: OscMessage) {}
+}
+
+#[test]
+fn add_reflected_osc_method() {
+    let mut app = App::new();
+    app.add_plugins(BevyRoscPlugin::default())
+        .add_reflected_osc_method::<ReflectedMethod>();
+
+    let registry = app.world().resource::<AppTypeRegistry>().read();
+    assert!(registry.contains(std::any::TypeId::of::<ReflectedMethod>()));
+}
@@ -0,0 +1,146 @@
+extern crate bevy_rosc;
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_rosc::{OscBindTarget, OscOutbox, OscPublish, OscPublishField, OscPublishMode};
+use common::{recorder_app, Recorder};
+use rosc::{OscPacket, OscTime, OscType};
+use std::thread::sleep;
+use std::time::Duration;
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct Level {
+    value: f32,
+}
+
+/// Spawns a recorder and an entity publishing its level to it
+fn spawn_publisher(mode: OscPublishMode, min_interval: Duration) -> (App, Entity, Entity) {
+    let mut app = recorder_app();
+    app.register_type::<Level>();
+
+    let recorder = app.world_mut().spawn(Recorder::default()).id();
+    let field = OscPublishField::new(
+        "/level".into(),
+        OscBindTarget::parse("Level.value").unwrap(),
+    )
+    .unwrap()
+    .with_min_interval(min_interval);
+    let publisher = app
+        .world_mut()
+        .spawn((
+            Level::default(),
+            OscPublish::new(mode)
+                .with_field(field)
+                .with_target(recorder),
+        ))
+        .id();
+    (app, recorder, publisher)
+}
+
+/// Returns the values of all recorded messages
+fn sent_values(app: &App, recorder: Entity) -> Vec<f32> {
+    app.world()
+        .get::<Recorder>(recorder)
+        .unwrap()
+        .0
+        .iter()
+        .map(|packet| match packet {
+            OscPacket::Message(message) => {
+                assert_eq!("/level", message.addr);
+                match message.args[..] {
+                    [OscType::Float(value)] => value,
+                    _ => panic!("Unexpected arguments {:?}", message.args),
+                }
+            }
+            OscPacket::Bundle(_) => panic!("Unexpected bundle"),
+        })
+        .collect()
+}
+
+fn set_level(app: &mut App, entity: Entity, value: f32) {
+    app.world_mut().get_mut::<Level>(entity).unwrap().value = value;
+}
+
+#[test]
+/// Fields are sent when they change, but not when their component changes to the same value
+fn publish_on_change() {
+    let (mut app, recorder, publisher) = spawn_publisher(OscPublishMode::OnChange, Duration::ZERO);
+
+    app.update();
+    assert_eq!(vec![0.0], sent_values(&app, recorder));
+
+    app.update();
+    assert_eq!(vec![0.0], sent_values(&app, recorder));
+
+    set_level(&mut app, publisher, 1.0);
+    app.update();
+    assert_eq!(vec![0.0, 1.0], sent_values(&app, recorder));
+
+    set_level(&mut app, publisher, 1.0);
+    app.update();
+    assert_eq!(vec![0.0, 1.0], sent_values(&app, recorder));
+}
+
+#[test]
+/// Changes within the rate limit are sent once it has passed
+fn publish_rate_limit() {
+    let (mut app, recorder, publisher) =
+        spawn_publisher(OscPublishMode::OnChange, Duration::from_millis(100));
+
+    app.update();
+    set_level(&mut app, publisher, 1.0);
+    app.update();
+    set_level(&mut app, publisher, 2.0);
+    app.update();
+    assert_eq!(vec![0.0], sent_values(&app, recorder));
+
+    sleep(Duration::from_millis(150));
+    app.update();
+    assert_eq!(vec![0.0, 2.0], sent_values(&app, recorder));
+}
+
+#[test]
+fn publish_on_request() {
+    let (mut app, recorder, publisher) = spawn_publisher(OscPublishMode::OnRequest, Duration::ZERO);
+
+    app.update();
+    set_level(&mut app, publisher, 1.0);
+    app.update();
+    assert!(sent_values(&app, recorder).is_empty());
+
+    app.world_mut()
+        .get_mut::<OscPublish>(publisher)
+        .unwrap()
+        .request();
+    app.update();
+    app.update();
+    assert_eq!(vec![1.0], sent_values(&app, recorder));
+}
+
+#[test]
+/// Targets with an outbox get the fields through it, in the same frame
+fn publish_through_outbox() {
+    let (mut app, recorder, _) = spawn_publisher(OscPublishMode::OnChange, Duration::ZERO);
+    app.world_mut()
+        .entity_mut(recorder)
+        .insert(OscOutbox::default().with_latency(Duration::from_secs(1)));
+
+    app.update();
+
+    match &app.world().get::<Recorder>(recorder).unwrap().0[..] {
+        [OscPacket::Bundle(bundle)] => {
+            assert_ne!(
+                OscTime {
+                    seconds: 0,
+                    fractional: 1
+                },
+                bundle.timetag
+            );
+            assert_eq!(1, bundle.content.len());
+        }
+        packets => panic!("Unexpected packets {:?}", packets),
+    }
+}
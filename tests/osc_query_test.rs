@@ -0,0 +1,188 @@
+#![cfg(feature = "oscquery")]
+extern crate bevy_rosc;
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_rosc::{
+    OscQueryInfo, OscQueryPlugin, OscQueryRange, OscQueryServer, SingleAddressOscMethod,
+};
+use common::{message, send, setup_app};
+use rosc::decoder::decode_udp;
+use rosc::{OscPacket, OscType};
+use serde_json::{json, Value};
+use std::io::{ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+use tungstenite::Message;
+
+/// Spawns a documented and an undocumented method behind an OSCQuery server
+fn query_app() -> App {
+    let mut app = setup_app();
+    app.add_plugins(
+        OscQueryPlugin::new(([127, 0, 0, 1], 0))
+            .with_name("test")
+            .with_osc_port(31337),
+    );
+    app.world_mut().spawn((
+        SingleAddressOscMethod::new("/mixer/volume".into()).unwrap(),
+        OscQueryInfo {
+            description: Some("Master volume".into()),
+            type_tag: Some("f".into()),
+            ranges: vec![OscQueryRange {
+                min: Some(0.0),
+                max: Some(1.0),
+            }],
+            ..Default::default()
+        },
+    ));
+    app.world_mut()
+        .spawn(SingleAddressOscMethod::new("/mixer/mute".into()).unwrap());
+    app
+}
+
+/// Sends a GET request and returns the status line and parsed body
+fn get(app: &App, target: &str) -> (String, Value) {
+    let addr = app.world().resource::<OscQueryServer>().local_addr();
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.lines().next().unwrap().to_string();
+    (status, serde_json::from_str(body).unwrap_or(Value::Null))
+}
+
+#[test]
+fn namespace_over_http() {
+    let mut app = query_app();
+    send(&mut app, message("/mixer/volume", vec![0.5f32.into()]));
+    app.update();
+
+    let (status, host_info) = get(&app, "/?HOST_INFO");
+    assert!(status.contains("200"));
+    assert_eq!(json!("test"), host_info["NAME"]);
+    assert_eq!(json!(31337), host_info["OSC_PORT"]);
+    assert_eq!(json!(true), host_info["EXTENSIONS"]["LISTEN"]);
+
+    let (_, root) = get(&app, "/");
+    let volume = &root["CONTENTS"]["mixer"]["CONTENTS"]["volume"];
+    assert_eq!(json!("/mixer/volume"), volume["FULL_PATH"]);
+    assert_eq!(json!("f"), volume["TYPE"]);
+    assert_eq!(json!([0.5]), volume["VALUE"]);
+    assert_eq!(json!([{"MIN": 0.0, "MAX": 1.0}]), volume["RANGE"]);
+    assert_eq!(json!("Master volume"), volume["DESCRIPTION"]);
+    assert_eq!(
+        json!("/mixer/mute"),
+        root["CONTENTS"]["mixer"]["CONTENTS"]["mute"]["FULL_PATH"]
+    );
+
+    assert_eq!(json!({"VALUE": [0.5]}), get(&app, "/mixer/volume?VALUE").1);
+    assert!(get(&app, "/nothing").0.contains("404"));
+}
+
+#[test]
+/// WebSocket clients receive the values of addresses they listen to
+fn listen_over_websocket() {
+    let mut app = query_app();
+    app.update();
+
+    let addr = app.world().resource::<OscQueryServer>().local_addr();
+    let stream = TcpStream::connect(addr).unwrap();
+    let (mut websocket, _) = tungstenite::client(format!("ws://{}/", addr), stream).unwrap();
+    websocket
+        .send(Message::Text(
+            json!({"COMMAND": "LISTEN", "DATA": "/mixer/volume"})
+                .to_string()
+                .into(),
+        ))
+        .unwrap();
+    websocket
+        .get_mut()
+        .set_read_timeout(Some(Duration::from_millis(10)))
+        .unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let packet = loop {
+        assert!(Instant::now() < deadline, "No value received");
+        send(&mut app, message("/mixer/mute", vec![true.into()]));
+        send(&mut app, message("/mixer/volume", vec![0.25f32.into()]));
+        app.update();
+
+        match websocket.read() {
+            Ok(Message::Binary(bytes)) => break decode_udp(&bytes).unwrap().1,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(e) => panic!("{}", e),
+        }
+    };
+
+    match packet {
+        OscPacket::Message(message) => {
+            assert_eq!("/mixer/volume", message.addr);
+            assert_eq!(vec![OscType::Float(0.25)], message.args);
+        }
+        OscPacket::Bundle(_) => panic!("Unexpected bundle"),
+    }
+}
+
+#[test]
+/// The namespace follows methods and infos being added, changed and removed
+fn namespace_updates() {
+    let mut app = query_app();
+    app.update();
+    let mixer = |app: &App| get(app, "/mixer").1["CONTENTS"].clone();
+
+    let solo = app
+        .world_mut()
+        .spawn(SingleAddressOscMethod::new("/mixer/solo".into()).unwrap())
+        .id();
+    app.update();
+    assert_eq!(json!("/mixer/solo"), mixer(&app)["solo"]["FULL_PATH"]);
+    assert_eq!(Value::Null, mixer(&app)["solo"]["DESCRIPTION"]);
+
+    app.world_mut().entity_mut(solo).insert(OscQueryInfo {
+        description: Some("Solo".into()),
+        ..Default::default()
+    });
+    app.update();
+    assert_eq!(json!("Solo"), mixer(&app)["solo"]["DESCRIPTION"]);
+
+    app.world_mut()
+        .get_mut::<OscQueryInfo>(solo)
+        .unwrap()
+        .description = Some("Solo channel".into());
+    app.update();
+    assert_eq!(json!("Solo channel"), mixer(&app)["solo"]["DESCRIPTION"]);
+
+    app.world_mut().entity_mut(solo).remove::<OscQueryInfo>();
+    app.update();
+    assert_eq!(Value::Null, mixer(&app)["solo"]["DESCRIPTION"]);
+
+    app.world_mut().despawn(solo);
+    app.update();
+    assert_eq!(Value::Null, mixer(&app)["solo"]);
+}
+
+#[test]
+/// Dropping the server closes open connections and stops listening
+fn drop_stops_server() {
+    let mut app = query_app();
+    app.update();
+
+    let addr = app.world().resource::<OscQueryServer>().local_addr();
+    let mut idle = TcpStream::connect(addr).unwrap();
+    let stream = TcpStream::connect(addr).unwrap();
+    let (mut websocket, _) = tungstenite::client(format!("ws://{}/", addr), stream).unwrap();
+
+    let started = Instant::now();
+    app.world_mut().remove_resource::<OscQueryServer>();
+    assert!(started.elapsed() < Duration::from_secs(1));
+
+    assert!(TcpStream::connect(addr).is_err());
+    assert_eq!(0, idle.read(&mut [0; 16]).unwrap_or(0));
+    assert!(!matches!(websocket.read(), Ok(Message::Binary(_))));
+}
@@ -0,0 +1,91 @@
+extern crate bevy_rosc;
+
+use bevy::prelude::*;
+
+use bevy_rosc::{
+    BevyRoscPlugin, OscOverflowPolicy, OscTransport, OscUdpServer, SingleAddressOscMethod,
+};
+use rosc::encoder::encode;
+use rosc::{OscMessage, OscPacket, OscType};
+use std::net::{SocketAddr, UdpSocket};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+fn message(value: i32) -> OscPacket {
+    OscPacket::Message(OscMessage {
+        addr: "/value".into(),
+        args: vec![OscType::Int(value)],
+    })
+}
+
+/// Send five messages and wait for the receive thread to handle them
+fn send_burst(addr: SocketAddr) {
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    for i in 0..5 {
+        peer.send_to(&encode(&message(i)).unwrap(), addr).unwrap();
+    }
+    sleep(Duration::from_millis(200));
+}
+
+fn receive_all(server: &mut OscUdpServer) -> Vec<OscPacket> {
+    let mut packets = vec![];
+    while let Some(received) = server.recv_packet().unwrap() {
+        packets.push(received.packet);
+    }
+    packets
+}
+
+#[test]
+/// A full queue drops packets according to the overflow policy and counts them
+fn overflow_policy() {
+    for (policy, expected) in [
+        (OscOverflowPolicy::DropOldest, [3, 4]),
+        (OscOverflowPolicy::DropNewest, [0, 1]),
+    ] {
+        let mut server = OscUdpServer::new("127.0.0.1:0")
+            .unwrap()
+            .with_receive_thread(2, policy)
+            .unwrap();
+        send_burst(server.local_addr().unwrap());
+
+        assert_eq!(expected.map(message).to_vec(), receive_all(&mut server));
+        assert_eq!(3, server.dropped_packets());
+    }
+}
+
+#[test]
+/// Packets are timestamped on arrival, not when the app gets to receive them
+fn dispatch_with_arrival_time() {
+    let mut app = App::new();
+    app.add_plugins(
+        BevyRoscPlugin::default()
+            .with_listener(([127, 0, 0, 1], 0))
+            .with_receive_thread(16, OscOverflowPolicy::DropOldest),
+    );
+    let method = app
+        .world_mut()
+        .spawn(SingleAddressOscMethod::new("/value".into()).unwrap())
+        .id();
+    let addr = app
+        .world_mut()
+        .query::<&OscUdpServer>()
+        .single(app.world())
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    send_burst(addr);
+    let before_update = Instant::now();
+    app.update();
+
+    let mut method = app
+        .world_mut()
+        .get_mut::<SingleAddressOscMethod>(method)
+        .unwrap();
+    let mut count = 0;
+    while let Some((_, meta)) = method.get_message_with_meta() {
+        assert!(meta.received_at < before_update);
+        count += 1;
+    }
+    assert_eq!(5, count);
+}
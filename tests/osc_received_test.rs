@@ -0,0 +1,166 @@
+extern crate bevy_rosc;
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_rosc::{
+    MultiAddressOscMethod, OscAppExt, OscMethod, OscReceived, OscUnmatched, SingleAddressOscMethod,
+};
+use common::{message, send, setup_app};
+use rosc::address::{Matcher, OscAddress};
+use rosc::OscMessage;
+
+#[derive(Resource, Default)]
+struct Received(Vec<(Entity, String)>);
+
+#[derive(Resource, Default)]
+struct Unmatched(Vec<String>);
+
+/// Records all triggered `OscReceived` and `OscUnmatched` events
+fn observed_app() -> App {
+    let mut app = setup_app();
+    app.init_resource::<Received>()
+        .init_resource::<Unmatched>()
+        .add_observer(
+            |trigger: Trigger<OscReceived>, mut received: ResMut<Received>| {
+                received
+                    .0
+                    .push((trigger.target(), trigger.message.addr.clone()));
+            },
+        )
+        .add_observer(
+            |trigger: Trigger<OscUnmatched>, mut unmatched: ResMut<Unmatched>| {
+                unmatched.0.push(trigger.message.addr.clone());
+            },
+        );
+    app
+}
+
+#[test]
+/// Matching entities are triggered, messages nobody matched are triggered globally
+fn trigger_received() {
+    let mut app = observed_app();
+    let single = app
+        .world_mut()
+        .spawn(SingleAddressOscMethod::new("/a/play".into()).unwrap())
+        .id();
+    let multi = app
+        .world_mut()
+        .spawn(MultiAddressOscMethod::new(vec!["/b/play".into(), "/b/stop".into()]).unwrap())
+        .id();
+
+    // The first update dispatches without address spaces, the second with them
+    for _ in 0..2 {
+        send(&mut app, message("/*/play", vec![]));
+        send(&mut app, message("/nothing", vec![]));
+        app.update();
+
+        let mut received = std::mem::take(&mut app.world_mut().resource_mut::<Received>().0);
+        received.sort();
+        let mut expected = vec![
+            (single, "/*/play".to_string()),
+            (multi, "/*/play".to_string()),
+        ];
+        expected.sort();
+        assert_eq!(expected, received);
+        assert_eq!(
+            vec!["/nothing".to_string()],
+            std::mem::take(&mut app.world_mut().resource_mut::<Unmatched>().0)
+        );
+    }
+}
+
+#[test]
+/// Entity observers only see the messages of their own entity
+fn entity_observer() {
+    let mut app = observed_app();
+    app.world_mut()
+        .spawn(SingleAddressOscMethod::new("/a".into()).unwrap())
+        .observe(|trigger: Trigger<OscReceived>, mut commands: Commands| {
+            commands.entity(trigger.target()).insert(Name::new("a"));
+        });
+    app.world_mut()
+        .spawn(SingleAddressOscMethod::new("/b".into()).unwrap());
+
+    send(&mut app, message("/b", vec![]));
+    app.update();
+    assert_eq!(
+        0,
+        app.world_mut().query::<&Name>().iter(app.world()).count()
+    );
+
+    send(&mut app, message("/a", vec![]));
+    app.update();
+    assert_eq!(
+        vec!["a"],
+        app.world_mut()
+            .query::<&Name>()
+            .iter(app.world())
+            .map(|name| name.as_str().to_string())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+/// Without observers, dispatched messages are never triggered as `OscReceived`
+fn no_observers() {
+    let mut app = setup_app();
+    let entity = app
+        .world_mut()
+        .spawn(SingleAddressOscMethod::new("/play".into()).unwrap())
+        .id();
+
+    send(&mut app, message("/play", vec![]));
+    app.update();
+    assert!(OscReceived::component_id(app.world()).is_none());
+
+    // Observers added later still receive messages
+    app.init_resource::<Received>();
+    app.world_mut().entity_mut(entity).observe(
+        |trigger: Trigger<OscReceived>, mut received: ResMut<Received>| {
+            received
+                .0
+                .push((trigger.target(), trigger.message.addr.clone()));
+        },
+    );
+    send(&mut app, message("/play", vec![]));
+    app.update();
+    assert_eq!(
+        app.world().resource::<Received>().0,
+        vec![(entity, "/play".to_string())]
+    );
+}
+
+/// Only accepts messages with arguments
+#[derive(Component)]
+struct WithArgs;
+
+impl OscMethod for WithArgs {
+    fn get_addresses(&self) -> Vec<OscAddress> {
+        vec![OscAddress::new("/volume".into()).unwrap()]
+    }
+    fn receive_message(&mut self, _osc_message: OscMessage) {}
+    fn match_message(&mut self, matcher: &Matcher, message: &OscMessage) -> bool {
+        !message.args.is_empty() && matcher.match_address(&self.get_addresses()[0])
+    }
+}
+
+#[test]
+/// Messages that a method rejected are unmatched, even if their address matches
+fn unmatched_by_method() {
+    let mut app = observed_app();
+    app.add_osc_method::<WithArgs>();
+    app.world_mut().spawn(WithArgs);
+
+    for _ in 0..2 {
+        send(&mut app, message("/volume", vec![]));
+        send(&mut app, message("/volume", vec![1.into()]));
+        app.update();
+
+        assert_eq!(
+            vec!["/volume".to_string()],
+            std::mem::take(&mut app.world_mut().resource_mut::<Unmatched>().0)
+        );
+    }
+}
@@ -0,0 +1,95 @@
+extern crate bevy_rosc;
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_rosc::{
+    BevyRoscPlugin, OscErrorEvent, OscMessageMeta, OscReplyEvent, OscUdpServer,
+    SingleAddressOscMethod,
+};
+use common::setup_app;
+use rosc::decoder::{decode_udp, MTU};
+use rosc::encoder::encode;
+use rosc::{OscMessage, OscPacket, OscType};
+use std::net::UdpSocket;
+use std::time::Duration;
+
+fn pong_system(
+    mut query: Query<&mut SingleAddressOscMethod>,
+    mut replies: EventWriter<OscReplyEvent>,
+) {
+    for mut method in &mut query {
+        while let Some((message, meta)) = method.get_message_with_meta() {
+            let pong = OscMessage {
+                addr: "/pong".into(),
+                args: message.args,
+            };
+            replies.write(OscReplyEvent::new(&meta, OscPacket::Message(pong)));
+        }
+    }
+}
+
+#[test]
+/// Replies are sent from the receiving server's socket back to the sender
+fn reply_to_sender() {
+    let mut app = App::new();
+    app.add_plugins(BevyRoscPlugin::default().with_listener(([127, 0, 0, 1], 0)))
+        .add_systems(Update, pong_system);
+    app.world_mut()
+        .spawn(SingleAddressOscMethod::new("/ping".into()).unwrap());
+    app.update();
+
+    let server_addr = app
+        .world_mut()
+        .query::<&OscUdpServer>()
+        .single(app.world())
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    peer.set_nonblocking(true).unwrap();
+    let ping = OscPacket::Message(OscMessage {
+        addr: "/ping".into(),
+        args: vec![OscType::Int(7)],
+    });
+    peer.send_to(&encode(&ping).unwrap(), server_addr).unwrap();
+
+    let mut buf = [0; MTU];
+    let (size, from) = (0..500)
+        .find_map(|_| {
+            app.update();
+            std::thread::sleep(Duration::from_millis(10));
+            peer.recv_from(&mut buf).ok()
+        })
+        .expect("No reply received");
+
+    assert_eq!(server_addr, from);
+    assert_eq!(
+        OscPacket::Message(OscMessage {
+            addr: "/pong".into(),
+            args: vec![OscType::Int(7)],
+        }),
+        decode_udp(&buf[..size]).unwrap().1
+    );
+}
+
+#[test]
+/// A reply to a message that didn't come through a transport is reported
+fn reply_without_server() {
+    let mut app = setup_app();
+    app.world_mut().send_event(OscReplyEvent::new(
+        &OscMessageMeta::now(),
+        OscPacket::Message(OscMessage {
+            addr: "/pong".into(),
+            args: vec![],
+        }),
+    ));
+    app.update();
+
+    let errors = app.world().resource::<Events<OscErrorEvent>>();
+    assert!(errors
+        .iter_current_update_events()
+        .any(|event| matches!(event, OscErrorEvent::Reply { .. })));
+}
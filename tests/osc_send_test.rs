@@ -0,0 +1,74 @@
+extern crate bevy_rosc;
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_rosc::{OscErrorEvent, OscSendEvent, OscSendTarget};
+use common::{recorder_app, Recorder};
+use rosc::decoder::{decode_udp, MTU};
+use rosc::{OscMessage, OscPacket};
+use std::net::UdpSocket;
+use std::time::Duration;
+
+fn ping() -> OscPacket {
+    OscPacket::Message(OscMessage {
+        addr: "/ping".into(),
+        args: vec![],
+    })
+}
+
+#[test]
+/// Packets are sent through transport entities given directly or by name, names of entities
+/// without a transport are ignored
+fn send_to_entity() {
+    let mut app = recorder_app();
+    let first = app.world_mut().spawn(Recorder::default()).id();
+    app.world_mut().spawn(Name::new("mixer"));
+    let second = app
+        .world_mut()
+        .spawn((Recorder::default(), Name::new("mixer")))
+        .id();
+
+    app.world_mut().send_event(OscSendEvent::new(first, ping()));
+    app.world_mut()
+        .send_event(OscSendEvent::new("mixer", ping()));
+    app.world_mut()
+        .send_event(OscSendEvent::new("unknown", ping()));
+    app.update();
+
+    assert_eq!(vec![ping()], app.world().get::<Recorder>(first).unwrap().0);
+    assert_eq!(vec![ping()], app.world().get::<Recorder>(second).unwrap().0);
+    let errors: Vec<_> = app
+        .world()
+        .resource::<Events<OscErrorEvent>>()
+        .iter_current_update_events()
+        .collect();
+    assert!(matches!(
+        errors.as_slice(),
+        [OscErrorEvent::SendTo {
+            target: OscSendTarget::Name(name),
+            ..
+        }] if name == "unknown"
+    ));
+
+    // Events are only sent once
+    app.update();
+    assert_eq!(1, app.world().get::<Recorder>(first).unwrap().0.len());
+}
+
+#[test]
+/// Packets can be sent to any address without a client entity
+fn send_to_addr() {
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    peer.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+    let mut app = recorder_app();
+    app.world_mut()
+        .send_event(OscSendEvent::new(peer.local_addr().unwrap(), ping()));
+    app.update();
+
+    let mut buf = [0; MTU];
+    let size = peer.recv(&mut buf).unwrap();
+    assert_eq!(ping(), decode_udp(&buf[..size]).unwrap().1);
+}
@@ -0,0 +1,159 @@
+extern crate bevy_rosc;
+
+use std::time::{Duration, Instant};
+
+use bevy_rosc::{
+    OscStreamDecoder, OscStreamFraming, OscTcpClient, OscTcpServer, OscTransportError,
+};
+use rosc::{OscBundle, OscMessage, OscPacket, OscTime, OscType};
+use std::io::Write;
+use std::net::TcpStream;
+
+fn message(addr: &str, args: Vec<OscType>) -> OscPacket {
+    OscPacket::Message(OscMessage {
+        addr: addr.into(),
+        args,
+    })
+}
+
+/// Poll the server until `count` packets were received or a timeout is hit
+fn recv_packets(server: &mut OscTcpServer, count: usize) -> Vec<OscPacket> {
+    let start = Instant::now();
+    let mut packets = vec![];
+    while packets.len() < count && start.elapsed() < Duration::from_secs(5) {
+        match server.recv() {
+            Ok(Some(packet)) => packets.push(packet),
+            Ok(None) => std::thread::sleep(Duration::from_millis(1)),
+            Err(e) => panic!("Unexpected error: {}", e),
+        }
+    }
+    packets
+}
+
+fn send_and_receive(framing: OscStreamFraming) {
+    let mut server = OscTcpServer::new("127.0.0.1:0", framing).unwrap();
+    let addr = server.local_addr().unwrap();
+
+    let mut client1 = OscTcpClient::new(addr, framing).unwrap();
+    let mut client2 = OscTcpClient::new(addr, framing).unwrap();
+
+    // 0xC0 and 0xDB have to be escaped when using SLIP
+    client1
+        .send(&message(
+            "/client1",
+            vec![OscType::Blob(vec![0xC0, 0xDB, 0x01])],
+        ))
+        .unwrap();
+    client2
+        .send(&message("/client2", vec![OscType::Int(2)]))
+        .unwrap();
+    client1
+        .send(&OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((0, 1)),
+            content: vec![message("/client1/bundled", vec![])],
+        }))
+        .unwrap();
+
+    let packets = recv_packets(&mut server, 3);
+    assert_eq!(3, packets.len());
+    assert_eq!(2, server.connection_count());
+    assert!(packets.contains(&message(
+        "/client1",
+        vec![OscType::Blob(vec![0xC0, 0xDB, 0x01])]
+    )));
+    assert!(packets.contains(&message("/client2", vec![OscType::Int(2)])));
+    assert!(packets.iter().any(|p| matches!(p, OscPacket::Bundle(_))));
+}
+
+#[test]
+/// Two clients send packets with OSC 1.0 length-prefix framing to the same server
+fn tcp_length_prefixed() {
+    send_and_receive(OscStreamFraming::LengthPrefixed);
+}
+
+#[test]
+/// Two clients send packets with OSC 1.1 SLIP framing to the same server
+fn tcp_slip() {
+    send_and_receive(OscStreamFraming::Slip);
+}
+
+#[test]
+/// Frames can arrive in arbitrary fragments, and broken frames must not affect the following ones
+fn stream_decoder_fragments() {
+    let framing = OscStreamFraming::Slip;
+    let frame = framing.encode(&message("/foo", vec![])).unwrap();
+
+    let mut decoder = OscStreamDecoder::new(framing);
+    decoder.push(&frame[..3]);
+    assert!(decoder.next_packet().unwrap().is_none());
+    decoder.push(&frame[3..]);
+    assert_eq!(
+        Some(message("/foo", vec![])),
+        decoder.next_packet().unwrap()
+    );
+
+    // Garbage frame followed by a valid one
+    decoder.push(&[0xC0, 0x01, 0x02, 0xC0]);
+    decoder.push(&frame);
+    assert!(decoder.next_packet().is_err());
+    assert_eq!(
+        Some(message("/foo", vec![])),
+        decoder.next_packet().unwrap()
+    );
+    assert!(decoder.next_packet().unwrap().is_none());
+}
+
+#[test]
+/// Frames larger than the maximum size are reported and close the connection
+fn max_frame_size() {
+    let mut decoder = OscStreamDecoder::new(OscStreamFraming::Slip).with_max_frame_size(16);
+    decoder.push(&[0xC0]);
+    decoder.push(&[0x01; 17]);
+    assert!(decoder.next_packet().is_err());
+    assert!(decoder.is_overflowed());
+    decoder.push(
+        &OscStreamFraming::Slip
+            .encode(&message("/foo", vec![]))
+            .unwrap(),
+    );
+    assert!(decoder.next_packet().unwrap().is_none());
+
+    let mut server = OscTcpServer::new("127.0.0.1:0", OscStreamFraming::LengthPrefixed)
+        .unwrap()
+        .with_max_frame_size(1024);
+    let mut peer = TcpStream::connect(server.local_addr().unwrap()).unwrap();
+    peer.write_all(&[0xFF; 8]).unwrap();
+
+    let start = Instant::now();
+    let error = loop {
+        match server.recv() {
+            Ok(None) if start.elapsed() < Duration::from_secs(5) => {
+                std::thread::sleep(Duration::from_millis(1))
+            }
+            result => break result,
+        }
+    };
+    assert!(matches!(error, Err(OscTransportError::DecodeError { .. })));
+    assert_eq!(0, server.connection_count());
+}
+
+#[test]
+/// Connections whose peer stops reading are closed once the send buffer is full
+fn max_send_buffer_size() {
+    let mut server = OscTcpServer::new("127.0.0.1:0", OscStreamFraming::LengthPrefixed)
+        .unwrap()
+        .with_max_send_buffer_size(1024);
+    let _peer = TcpStream::connect(server.local_addr().unwrap()).unwrap();
+
+    let start = Instant::now();
+    while server.connection_count() == 0 && start.elapsed() < Duration::from_secs(5) {
+        server.recv().unwrap();
+    }
+    assert_eq!(1, server.connection_count());
+
+    let packet = message("/data", vec![OscType::Blob(vec![0; 1024])]);
+    let error = (0..100_000).find_map(|_| server.broadcast(&packet).err());
+    assert!(matches!(error, Some(OscTransportError::IoError(_))));
+    server.recv().unwrap();
+    assert_eq!(0, server.connection_count());
+}
@@ -0,0 +1,170 @@
+extern crate bevy_rosc;
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_rosc::{
+    method_dispatcher_system, osc_receive_system, OscDispatchEvent, OscErrorEvent, OscLoopback,
+    OscTransport, OscTransportError, OscUdpServer, SingleAddressOscMethod,
+};
+use common::dispatcher_app;
+use rosc::{OscBundle, OscMessage, OscPacket, OscTime};
+use std::net::{SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+#[test]
+/// Packets sent through a loopback transport are received by the generic receive system and
+/// delivered to matching methods
+fn loopback_transport() {
+    let mut app = dispatcher_app(method_dispatcher_system::<SingleAddressOscMethod>);
+
+    let method = app
+        .world_mut()
+        .spawn(SingleAddressOscMethod::new("/loop".into()).unwrap())
+        .id();
+
+    let mut loopback = OscLoopback::new();
+    loopback
+        .send_packet(
+            &OscPacket::Message(OscMessage {
+                addr: "/loop".into(),
+                args: vec![1.into()],
+            }),
+            None,
+        )
+        .unwrap();
+    assert_eq!(1, loopback.len());
+    app.world_mut().spawn(loopback);
+
+    app.update();
+
+    let mut method = app
+        .world_mut()
+        .get_mut::<SingleAddressOscMethod>(method)
+        .unwrap();
+    assert_eq!(
+        vec![rosc::OscType::Int(1)],
+        method.get_message().unwrap().args
+    );
+    assert!(method.get_message().is_none());
+}
+
+#[test]
+/// The sender's address, the receiving transport and the bundle timetag are attached to every
+/// dispatched message
+fn received_message_meta() {
+    let mut app = dispatcher_app(method_dispatcher_system::<SingleAddressOscMethod>);
+
+    let method = app
+        .world_mut()
+        .spawn(SingleAddressOscMethod::new("/meta".into()).unwrap())
+        .id();
+
+    let source: SocketAddr = "10.0.0.2:9000".parse().unwrap();
+    let timetag = OscTime::from((3_900_000_000, 0));
+    let mut loopback = OscLoopback::new();
+    loopback.push(
+        OscPacket::Bundle(OscBundle {
+            timetag,
+            content: vec![OscPacket::Message(OscMessage {
+                addr: "/meta".into(),
+                args: vec![],
+            })],
+        }),
+        Some(source),
+    );
+    let server = app.world_mut().spawn(loopback).id();
+
+    let before = Instant::now();
+    app.update();
+
+    let mut method = app
+        .world_mut()
+        .get_mut::<SingleAddressOscMethod>(method)
+        .unwrap();
+    let (_, meta) = method.get_message_with_meta().unwrap();
+    assert_eq!(Some(source), meta.source);
+    assert_eq!(Some(server), meta.server);
+    assert_eq!(Some(timetag), meta.timetag);
+    assert!(meta.received_at <= before);
+}
+
+#[test]
+/// Messages with a bad address pattern and undecodable packets are reported as error events, while
+/// all other messages are still delivered
+fn receive_and_dispatch_errors() {
+    let mut app = dispatcher_app(method_dispatcher_system::<SingleAddressOscMethod>);
+    app.add_systems(
+        Update,
+        osc_receive_system::<OscUdpServer>
+            .before(method_dispatcher_system::<SingleAddressOscMethod>),
+    );
+
+    let method = app
+        .world_mut()
+        .spawn(SingleAddressOscMethod::new("/good".into()).unwrap())
+        .id();
+
+    let mut loopback = OscLoopback::new();
+    for addr in ["/bad[", "/good"] {
+        loopback.push(
+            OscPacket::Message(OscMessage {
+                addr: addr.into(),
+                args: vec![],
+            }),
+            None,
+        );
+    }
+    app.world_mut().spawn(loopback);
+
+    let udp_server = OscUdpServer::new("127.0.0.1:0").unwrap();
+    let udp_addr = udp_server.local_addr().unwrap();
+    let udp_server = app.world_mut().spawn(udp_server).id();
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.send_to(&[1, 2, 3], udp_addr).unwrap();
+    // Give the datagram some time to arrive
+    std::thread::sleep(Duration::from_millis(50));
+
+    app.update();
+
+    let errors: Vec<&OscErrorEvent> = app
+        .world()
+        .resource::<Events<OscErrorEvent>>()
+        .iter_current_update_events()
+        .collect();
+    assert_eq!(2, errors.len());
+    assert!(errors.iter().any(|e| matches!(
+        e,
+        OscErrorEvent::Dispatch { address, .. } if address == "/bad["
+    )));
+    assert!(errors.iter().any(|e| matches!(
+        e,
+        OscErrorEvent::Receive {
+            server,
+            error: OscTransportError::DecodeError { bytes, source: Some(source), .. },
+        } if *server == udp_server && bytes == &[1, 2, 3] && *source == socket.local_addr().unwrap()
+    )));
+
+    let mut method = app
+        .world_mut()
+        .get_mut::<SingleAddressOscMethod>(method)
+        .unwrap();
+    assert!(method.get_message().is_some());
+}
+
+#[test]
+/// Frames without received messages don't send an `OscDispatchEvent`
+fn skip_empty_batches() {
+    let mut app = dispatcher_app(method_dispatcher_system::<SingleAddressOscMethod>);
+    app.world_mut().spawn(OscLoopback::new());
+    app.update();
+
+    assert_eq!(
+        0,
+        app.world()
+            .resource::<Events<OscDispatchEvent>>()
+            .iter_current_update_events()
+            .count()
+    );
+}
@@ -0,0 +1,92 @@
+extern crate bevy_rosc;
+
+use bevy_rosc::{OscTransport, OscUdpClient, OscUdpOptions, OscUdpServer};
+use rosc::{OscMessage, OscPacket};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::thread::sleep;
+use std::time::Duration;
+
+fn ping() -> OscPacket {
+    OscPacket::Message(OscMessage {
+        addr: "/ping".into(),
+        args: vec![],
+    })
+}
+
+#[test]
+/// Servers with address reuse can share a port
+fn reuse_address() {
+    let options = OscUdpOptions::new().with_reuse_address(true);
+    let first = OscUdpServer::with_options("127.0.0.1:0", &options).unwrap();
+    let addr = first.local_addr().unwrap();
+    let second = OscUdpServer::with_options(addr, &options).unwrap();
+    assert_eq!(addr, second.local_addr().unwrap());
+
+    assert!(OscUdpServer::new(addr).is_err());
+}
+
+#[test]
+/// A server receives packets sent to a multicast group it joined
+fn multicast() {
+    let group = Ipv4Addr::new(239, 255, 43, 21);
+    let mut server = OscUdpServer::with_options(
+        "0.0.0.0:0",
+        &OscUdpOptions::new()
+            .with_reuse_address(true)
+            .join_multicast_v4(group, Ipv4Addr::LOCALHOST),
+    )
+    .unwrap();
+    let port = server.local_addr().unwrap().port();
+
+    let client = OscUdpClient::with_options(
+        SocketAddr::from((group, port)),
+        &OscUdpOptions::new()
+            .with_multicast_interface_v4(Ipv4Addr::LOCALHOST)
+            .with_multicast_ttl(1)
+            .with_multicast_loop(true),
+    )
+    .unwrap();
+    client.send(&ping()).unwrap();
+
+    let received = (0..500)
+        .find_map(|_| {
+            sleep(Duration::from_millis(10));
+            server.recv_packet().unwrap()
+        })
+        .expect("No packet received");
+    assert_eq!(ping(), received.packet);
+}
+
+#[test]
+/// A server on `[::]` receives from IPv4 and IPv6 clients and reports IPv4 sources as such
+fn dual_stack() {
+    let mut server = OscUdpServer::new("[::]:0").unwrap();
+    let port = server.local_addr().unwrap().port();
+
+    for target in [
+        SocketAddr::from((Ipv4Addr::LOCALHOST, port)),
+        SocketAddr::from((Ipv6Addr::LOCALHOST, port)),
+    ] {
+        let mut client = OscUdpClient::new(target).unwrap();
+        client.send(&ping()).unwrap();
+
+        let received = (0..500)
+            .find_map(|_| {
+                sleep(Duration::from_millis(10));
+                server.recv_packet().unwrap()
+            })
+            .expect("No packet received");
+        let source = received.source.unwrap();
+        assert_eq!(target.is_ipv4(), source.is_ipv4());
+
+        // Replies reach the client from the IPv6 socket
+        server.send_to(&ping(), source).unwrap();
+        let reply = (0..500)
+            .find_map(|_| {
+                sleep(Duration::from_millis(10));
+                client.recv_packet().unwrap()
+            })
+            .expect("No reply received");
+        assert_eq!(ping(), reply.packet);
+    }
+}
@@ -0,0 +1,115 @@
+#![cfg(unix)]
+extern crate bevy_rosc;
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_rosc::{
+    OscOverflowPolicy, OscStreamFraming, OscTransport, OscUnixDatagramClient,
+    OscUnixDatagramServer, OscUnixStreamClient, OscUnixStreamServer, SingleAddressOscMethod,
+};
+use common::setup_app;
+use rosc::{OscMessage, OscPacket, OscType};
+use std::thread::sleep;
+use std::time::Duration;
+
+fn message(value: i32) -> OscMessage {
+    OscMessage {
+        addr: "/volume".into(),
+        args: vec![OscType::Int(value)],
+    }
+}
+
+#[test]
+/// Packets received on a Unix datagram socket are dispatched, the socket file is removed on drop
+fn datagram_dispatch() {
+    let path = std::env::temp_dir().join(format!("bevy_rosc_test_{}.sock", std::process::id()));
+
+    let mut app = setup_app();
+    let server = app
+        .world_mut()
+        .spawn(OscUnixDatagramServer::bind(&path).unwrap())
+        .id();
+    let method = app
+        .world_mut()
+        .spawn(SingleAddressOscMethod::new("/volume".into()).unwrap())
+        .id();
+
+    let client = OscUnixDatagramClient::connect(&path).unwrap();
+    client.send(&OscPacket::Message(message(3))).unwrap();
+    app.update();
+
+    let mut method = app
+        .world_mut()
+        .get_mut::<SingleAddressOscMethod>(method)
+        .unwrap();
+    assert_eq!(Some(message(3)), method.get_message());
+
+    assert!(path.exists());
+    app.world_mut().despawn(server);
+    assert!(!path.exists());
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+/// Packets are exchanged in both directions over an abstract Unix stream socket
+fn abstract_stream() {
+    let name = format!("bevy_rosc_test_{}", std::process::id());
+    let mut server = OscUnixStreamServer::bind_abstract(&name, OscStreamFraming::Slip).unwrap();
+    let mut client = OscUnixStreamClient::connect_abstract(&name, OscStreamFraming::Slip).unwrap();
+
+    client.send(&OscPacket::Message(message(1))).unwrap();
+    client.send(&OscPacket::Message(message(2))).unwrap();
+    let mut received = vec![];
+    for _ in 0..500 {
+        while let Some(packet) = server.recv().unwrap() {
+            received.push(packet);
+        }
+        if received.len() == 2 {
+            break;
+        }
+        sleep(Duration::from_millis(1));
+    }
+    assert_eq!(
+        vec![
+            OscPacket::Message(message(1)),
+            OscPacket::Message(message(2))
+        ],
+        received
+    );
+    assert_eq!(1, server.connection_count());
+
+    server
+        .send_packet(&OscPacket::Message(message(4)), None)
+        .unwrap();
+    let reply = (0..500)
+        .find_map(|_| {
+            sleep(Duration::from_millis(1));
+            client.recv().unwrap()
+        })
+        .expect("Reply not received");
+    assert_eq!(OscPacket::Message(message(4)), reply);
+}
+
+#[test]
+/// A Unix datagram server can receive on its own thread, dropping packets when its queue is full
+fn datagram_receive_thread() {
+    let path =
+        std::env::temp_dir().join(format!("bevy_rosc_thread_test_{}.sock", std::process::id()));
+    let server = OscUnixDatagramServer::bind(&path)
+        .unwrap()
+        .with_receive_thread(2, OscOverflowPolicy::DropOldest)
+        .unwrap();
+
+    let client = OscUnixDatagramClient::connect(&path).unwrap();
+    for value in 0..5 {
+        client.send(&OscPacket::Message(message(value))).unwrap();
+    }
+    sleep(Duration::from_millis(200));
+
+    assert_eq!(Some(OscPacket::Message(message(3))), server.recv().unwrap());
+    assert_eq!(Some(OscPacket::Message(message(4))), server.recv().unwrap());
+    assert_eq!(None, server.recv().unwrap());
+    assert_eq!(3, server.dropped_packets());
+}